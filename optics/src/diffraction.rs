@@ -0,0 +1,350 @@
+use std::f32::consts::{PI, TAU};
+
+use rayon::prelude::*;
+
+use crate::{
+    fft::fft_2d,
+    fields::FieldPoint,
+    glam::{DVec2, DVec3, Vec2},
+    pupil::pupil_grid,
+    ray::Wavelength,
+    system::System,
+};
+
+/// Point spread function of a single wavelength, sampled on a square grid of the image surface
+/// centered on the point where the chief ray of the primary wavelength reaches it.
+#[derive(Debug, Clone)]
+pub struct Psf {
+    pub wavelength: Wavelength,
+    /// Number of samples on each side of the grid.
+    pub size: usize,
+    /// Distance between neighbouring samples.
+    pub spacing: f32,
+    /// Intensity of each sample, row by row from -Y to +Y, relative to the peak intensity of the
+    /// same pupil without aberrations.
+    pub values: Vec<f32>,
+    /// Largest value of the grid, which estimates the Strehl ratio.
+    pub peak: f32,
+}
+
+impl Psf {
+    /// Position of the sample at `row` and `column` of the grid, relative to its center.
+    pub fn position(&self, row: usize, column: usize) -> Vec2 {
+        let center = (self.size / 2) as f32;
+
+        Vec2::new(column as f32 - center, row as f32 - center) * self.spacing
+    }
+}
+
+/// Modulation transfer function of a field point, weighted over the wavelengths of the system.
+#[derive(Debug, Clone)]
+pub struct Mtf {
+    /// Spatial frequencies, in cycles per millimeter.
+    pub frequencies: Vec<f32>,
+    /// Modulation of lines parallel to X, that vary along Y.
+    pub tangential: Vec<f32>,
+    /// Modulation of lines parallel to Y, that vary along X.
+    pub sagittal: Vec<f32>,
+    /// Modulation of an unaberrated circular pupil with the same F-number.
+    pub diffraction_limit: Vec<f32>,
+}
+
+/// MTF of a single wavelength, sampled every `step` cycles per millimeter up to `cutoff`.
+struct MonochromaticMtf {
+    step: f32,
+    cutoff: f32,
+    tangential: Vec<f32>,
+    sagittal: Vec<f32>,
+}
+
+/// Linear interpolation of `values`, sampled every `step`, at `x`. Values beyond the last sample
+/// are zero.
+fn interpolate(values: &[f32], step: f32, x: f32) -> f32 {
+    let position = x / step;
+    let i = position.floor() as usize;
+
+    match (values.get(i), values.get(i + 1)) {
+        (Some(a), Some(b)) => a + (b - a) * position.fract(),
+        (Some(a), None) if position.fract() < 1e-4 => *a,
+        _ => 0.0,
+    }
+}
+
+/// MTF of an unaberrated circular pupil at `frequency`, relative to its `cutoff` frequency.
+fn diffraction_limit(frequency: f32, cutoff: f32) -> f32 {
+    let normalized = (frequency / cutoff).clamp(0.0, 1.0);
+
+    2.0 / PI * (normalized.acos() - normalized * (1.0 - normalized * normalized).sqrt())
+}
+
+// Implementation of diffraction methods
+impl System {
+    /// Amplitude of the field on the image of `point` at `wavelength`, computed by the Fourier
+    /// transform of its pupil function. The pupil is sampled with `sampling` by `sampling` points,
+    /// see [`System::wavefront`], where vignetted rays have no amplitude and the phase comes from
+    /// the optical path difference. The samples are padded with zeros to a grid of the next power
+    /// of two at least twice as large, which is returned row by row without being centered, along
+    /// with its size and the distance between samples on the image.
+    fn fft_field(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+        sampling: usize,
+    ) -> Option<(Vec<Vec2>, usize, f32)> {
+        let sampling = sampling.max(2);
+        let wavefront = self.wavefront(point, wavelength, sampling)?;
+        let f_number = self.first_order(wavelength)?.working_f_number;
+
+        let size = (2 * sampling).next_power_of_two();
+        let mut field = vec![Vec2::ZERO; size * size];
+        let mut transmitted = 0;
+
+        for (i, value) in wavefront.values.iter().enumerate() {
+            if let Some(value) = value {
+                field[i / sampling * size + i % sampling] = Vec2::from_angle(TAU * value);
+                transmitted += 1;
+            }
+        }
+
+        if transmitted == 0 {
+            return None;
+        }
+
+        // The transform with exp(2πi k n / N) keeps the orientation of the pupil on the image
+        fft_2d(&mut field, size, true);
+
+        // Without aberrations, every sample adds up in phase at the center of the image
+        let scale = (transmitted as f32).recip();
+        field.iter_mut().for_each(|amplitude| *amplitude *= scale);
+
+        // The pupil spans `sampling - 1` samples, which sets the image sampling to λ N / that
+        // many samples of the padded grid, where wavelengths are in micrometers
+        let spacing = wavelength * 1e-3 * f_number * (sampling - 1) as f32 / size as f32;
+
+        Some((field, size, spacing))
+    }
+
+    /// Point spread function of `point` at `wavelength`, from the Fourier transform of a pupil
+    /// sampled with `sampling` by `sampling` points. Returns `None` when the field point cannot be
+    /// traced or the system is not supported by paraxial calculations.
+    pub fn fft_psf(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+        sampling: usize,
+    ) -> Option<Psf> {
+        let (field, size, spacing) = self.fft_field(point, wavelength, sampling)?;
+
+        // Moves the origin of the image to the center of the grid
+        let half = size / 2;
+        let values: Vec<_> = (0..size * size)
+            .into_par_iter()
+            .map(|i| {
+                let (row, column) = ((i / size + half) % size, (i % size + half) % size);
+
+                field[row * size + column].length_squared()
+            })
+            .collect();
+
+        Some(Psf {
+            wavelength,
+            size,
+            spacing,
+            peak: values.iter().copied().fold(0.0, f32::max),
+            values,
+        })
+    }
+
+    /// MTF of `point` at `wavelength`, from the Fourier transform of its PSF.
+    fn monochromatic_mtf(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+        sampling: usize,
+    ) -> Option<MonochromaticMtf> {
+        let sampling = sampling.max(2);
+        let (field, size, spacing) = self.fft_field(point, wavelength, sampling)?;
+
+        let mut transfer: Vec<_> = field
+            .par_iter()
+            .map(|amplitude| Vec2::new(amplitude.length_squared(), 0.0))
+            .collect();
+
+        fft_2d(&mut transfer, size, false);
+
+        let zero = transfer[0].length();
+
+        if zero <= 0.0 {
+            return None;
+        }
+
+        // The autocorrelation of the pupil vanishes past its diameter, `sampling - 1` samples
+        let step = (size as f32 * spacing).recip();
+
+        Some(MonochromaticMtf {
+            step,
+            cutoff: (sampling - 1) as f32 * step,
+            tangential: (0..sampling)
+                .map(|k| transfer[k * size].length() / zero)
+                .collect(),
+            sagittal: (0..sampling).map(|k| transfer[k].length() / zero).collect(),
+        })
+    }
+
+    /// Diffraction MTF of `point`, weighted over the wavelengths of the system. Each wavelength is
+    /// computed from the Fourier transform of a pupil sampled with `sampling` by `sampling`
+    /// points, and the curves go from zero up to the highest cutoff frequency with `sampling`
+    /// points. Returns `None` when the field point cannot be traced or the system is not supported
+    /// by paraxial calculations.
+    pub fn fft_mtf(&self, point: &FieldPoint, sampling: usize) -> Option<Mtf> {
+        let sampling = sampling.max(2);
+        let wavelengths: Vec<_> = self.weighted_wavelengths().collect();
+
+        let curves = wavelengths
+            .par_iter()
+            .map(|&(wavelength, weight)| {
+                Some((self.monochromatic_mtf(point, wavelength, sampling)?, weight))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let cutoff = curves
+            .iter()
+            .map(|(curve, _)| curve.cutoff)
+            .fold(0.0, f32::max);
+
+        let frequencies: Vec<_> = (0..sampling)
+            .map(|i| cutoff * i as f32 / (sampling - 1) as f32)
+            .collect();
+
+        let weighted = |value: &dyn Fn(&MonochromaticMtf, f32) -> f32| -> Vec<f32> {
+            frequencies
+                .iter()
+                .map(|&frequency| {
+                    curves
+                        .iter()
+                        .map(|(curve, weight)| weight * value(curve, frequency))
+                        .sum()
+                })
+                .collect()
+        };
+
+        Some(Mtf {
+            tangential: weighted(&|curve, frequency| {
+                interpolate(&curve.tangential, curve.step, frequency)
+            }),
+            sagittal: weighted(&|curve, frequency| {
+                interpolate(&curve.sagittal, curve.step, frequency)
+            }),
+            diffraction_limit: weighted(&|curve, frequency| {
+                diffraction_limit(frequency, curve.cutoff)
+            }),
+            frequencies,
+        })
+    }
+
+    /// Point spread function of `point` at `wavelength` on the plane of the image surface, from the
+    /// direct sum of Huygens wavelets. Unlike [`System::fft_psf`], the plane does not need to be
+    /// perpendicular to the chief ray, which makes it suitable for tilted image surfaces.
+    ///
+    /// Wavelets leave the reference sphere where the rays of a `sampling` by `sampling` grid over
+    /// the pupil cross it, see [`pupil_grid`], with the phase of their optical path difference.
+    /// Each one is weighted by the cosine of its incidence on the plane and the inverse of the
+    /// distance it travels. The PSF is sampled on a grid of `size` by `size` points `spacing`
+    /// apart along the X and Y axes of the image surface.
+    ///
+    /// Returns `None` when the field point cannot be traced, no ray reaches the image or the image
+    /// space is telecentric, since wavelets cannot start from an exit pupil at infinity.
+    pub fn huygens_psf(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+        sampling: usize,
+        size: usize,
+        spacing: f32,
+    ) -> Option<Psf> {
+        let field = self.object_field(point, wavelength)?;
+        let sphere = self.reference_sphere(point, wavelength)?;
+        let pupil = sphere.pupil?;
+
+        let grid: Vec<_> = pupil_grid(sampling).collect();
+        let wavelets: Vec<_> = grid
+            .par_iter()
+            .filter_map(|pupil| {
+                let trace = self.trace(self.aim(field, point.vignetting.apply(*pupil), wavelength));
+
+                if !trace.is_complete() {
+                    return None;
+                }
+
+                let record = trace.last()?;
+
+                Some((
+                    sphere.crossing(record).as_dvec3(),
+                    sphere.opd(record) as f64,
+                    record.refractive_index.abs() as f64,
+                ))
+            })
+            .collect();
+
+        if wavelets.is_empty() {
+            return None;
+        }
+
+        // Distances are measured against the radius of the sphere, which keeps the phases small
+        // enough to be accurate. Wavelengths are in micrometers and lengths in millimeters.
+        let center = sphere.center.as_dvec3();
+        let radius = (pupil - sphere.center).length() as f64;
+        let wavenumber = std::f64::consts::TAU / (wavelength as f64 * 1e-3);
+
+        let transform = self.image_transform().as_dmat4();
+        let normal = transform.transform_vector3(DVec3::Z).normalize();
+
+        let amplitude = |target: DVec3, aberrated: bool| -> DVec2 {
+            wavelets
+                .iter()
+                .map(|&(origin, opd, index)| {
+                    let offset = target - origin;
+                    let distance = offset.length();
+                    let weight = offset.dot(normal).abs() / (distance * distance);
+
+                    if aberrated {
+                        let phase =
+                            index * wavenumber * (distance - radius) - std::f64::consts::TAU * opd;
+
+                        DVec2::from_angle(phase) * weight
+                    } else {
+                        DVec2::new(weight, 0.0)
+                    }
+                })
+                .sum()
+        };
+
+        // Without aberrations, every wavelet adds up in phase at the center of the sphere
+        let reference = amplitude(center, false).length_squared();
+
+        if reference <= 0.0 {
+            return None;
+        }
+
+        let mut psf = Psf {
+            wavelength,
+            size,
+            spacing,
+            values: Vec::new(),
+            peak: 0.0,
+        };
+
+        psf.values = (0..size * size)
+            .into_par_iter()
+            .map(|i| {
+                let position = psf.position(i / size, i % size).as_dvec2();
+                let target = center + transform.transform_vector3(position.extend(0.0));
+
+                (amplitude(target, true).length_squared() / reference) as f32
+            })
+            .collect();
+        psf.peak = psf.values.iter().copied().fold(0.0, f32::max);
+
+        Some(psf)
+    }
+}
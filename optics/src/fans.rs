@@ -0,0 +1,62 @@
+use rayon::prelude::*;
+
+use crate::{fields::FieldPoint, glam::Vec2, pupil::pupil_line, ray::Wavelength, system::System};
+
+/// Transverse ray aberrations of a single wavelength across the pupil of a field point.
+///
+/// Each fan holds points made of a normalized pupil coordinate and the distance, on the image
+/// surface, from the ray that goes through it to the chief ray of the primary wavelength. Vignetted
+/// rays are left out.
+#[derive(Debug, Clone)]
+pub struct RayFan {
+    pub wavelength: Wavelength,
+    /// Error along Y of rays on the YZ plane of the pupil, as (Py, ey).
+    pub tangential: Vec<Vec2>,
+    /// Error along X of rays on the XZ plane of the pupil, as (Px, ex).
+    pub sagittal: Vec<Vec2>,
+}
+
+// Implementation of ray fan methods
+impl System {
+    /// Tangential and sagittal fans of `point` for every wavelength, each with `samples` rays.
+    /// Returns `None` when the field point cannot be defined or its chief ray cannot be traced.
+    pub fn ray_fans(&self, point: &FieldPoint, samples: usize) -> Option<Vec<RayFan>> {
+        let reference = self.chief_ray_image(point, self.primary_wavelength())?;
+        let coordinates: Vec<_> = pupil_line(samples).collect();
+
+        let fans = self
+            .wavelengths
+            .iter()
+            .map(|wavelength| {
+                let wavelength = wavelength.value;
+                let field = self.object_field(point, wavelength);
+
+                // Image point of the ray through `pupil`, relative to the reference
+                let error = |pupil: Vec2| {
+                    let ray = self.aim(field?, point.vignetting.apply(pupil), wavelength);
+                    let trace = self.trace(ray);
+
+                    if !trace.is_complete() {
+                        return None;
+                    }
+
+                    Some(trace.last()?.local_point.truncate() - reference)
+                };
+
+                RayFan {
+                    wavelength,
+                    tangential: coordinates
+                        .par_iter()
+                        .filter_map(|&p| Some(Vec2::new(p, error(Vec2::new(0.0, p))?.y)))
+                        .collect(),
+                    sagittal: coordinates
+                        .par_iter()
+                        .filter_map(|&p| Some(Vec2::new(p, error(Vec2::new(p, 0.0))?.x)))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Some(fans)
+    }
+}
@@ -0,0 +1,72 @@
+use std::f32::consts::TAU;
+
+use rayon::prelude::*;
+
+use crate::glam::Vec2;
+
+/// Discrete Fourier transform of `data` in place, where complex numbers are stored as (re, im).
+/// The forward transform uses exp(-2πi k n / N) and the inverse one is not scaled. The length of
+/// `data` must be a power of two.
+pub(crate) fn fft(data: &mut [Vec2], inverse: bool) {
+    let len = data.len();
+    debug_assert!(len.is_power_of_two());
+
+    if len <= 1 {
+        return;
+    }
+
+    // Bit reversal permutation
+    let bits = len.trailing_zeros();
+
+    for i in 0..len {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut half = 1;
+
+    // Butterflies, merging transforms of length `half` into transforms of twice that length
+    while half < len {
+        let step = Vec2::from_angle(sign * TAU / (2 * half) as f32);
+
+        for start in (0..len).step_by(2 * half) {
+            let mut twiddle = Vec2::X;
+
+            for k in start..start + half {
+                let odd = twiddle.rotate(data[k + half]);
+
+                data[k + half] = data[k] - odd;
+                data[k] += odd;
+                twiddle = twiddle.rotate(step);
+            }
+        }
+
+        half *= 2;
+    }
+}
+
+/// Two-dimensional discrete Fourier transform of `data` in place, stored row by row with `size`
+/// elements per row, see [`fft`]. Rows and columns are transformed in parallel.
+pub(crate) fn fft_2d(data: &mut [Vec2], size: usize, inverse: bool) {
+    debug_assert_eq!(data.len(), size * size);
+
+    data.par_chunks_mut(size).for_each(|row| fft(row, inverse));
+
+    let mut transposed = transpose(data, size);
+    transposed
+        .par_chunks_mut(size)
+        .for_each(|column| fft(column, inverse));
+
+    data.copy_from_slice(&transpose(&transposed, size));
+}
+
+/// Transpose of a square matrix stored row by row with `size` elements per row.
+fn transpose(data: &[Vec2], size: usize) -> Vec<Vec2> {
+    (0..size * size)
+        .map(|i| data[(i % size) * size + i / size])
+        .collect()
+}
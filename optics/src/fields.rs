@@ -0,0 +1,195 @@
+use crate::{
+    first_order::ParaxialModel,
+    glam::Vec2,
+    ray::{Ray, Wavelength},
+    solver::newton,
+    surface::{Meridian, THICKNESS},
+    system::System,
+};
+
+/// Quantity used to define field points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldType {
+    /// Angle, in degrees, that the chief ray makes with the optical axis in object space.
+    #[default]
+    ObjectAngle,
+    /// Point of the object plane, which must be at a finite distance.
+    ObjectHeight,
+    /// Point where the paraxial chief ray reaches the image surface.
+    ParaxialImageHeight,
+    /// Point where the real chief ray reaches the image surface.
+    RealImageHeight,
+}
+
+impl FieldType {
+    pub const ALL: [Self; 4] = [
+        Self::ObjectAngle,
+        Self::ObjectHeight,
+        Self::ParaxialImageHeight,
+        Self::RealImageHeight,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ObjectAngle => "Object angle",
+            Self::ObjectHeight => "Object height",
+            Self::ParaxialImageHeight => "Paraxial image height",
+            Self::RealImageHeight => "Real image height",
+        }
+    }
+
+    /// Whether field points are angles instead of lengths.
+    pub const fn is_angle(&self) -> bool {
+        matches!(self, Self::ObjectAngle)
+    }
+}
+
+/// Vignetting factors, which shrink and decenter the pupil of a field point to follow the beam that
+/// actually goes through the system.
+///
+/// Normalized pupil coordinates are first compressed by `compression` and shifted by `decenter`,
+/// then rotated by `angle`, in degrees.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vignetting {
+    pub decenter: Vec2,
+    pub compression: Vec2,
+    pub angle: f32,
+}
+
+impl Vignetting {
+    /// Vignetted pupil coordinates of `pupil`.
+    pub fn apply(&self, pupil: Vec2) -> Vec2 {
+        let point = self.decenter + pupil * (Vec2::ONE - self.compression);
+
+        Vec2::from_angle(self.angle.to_radians()).rotate(point)
+    }
+}
+
+/// Point of the field of view, in the units of the [`FieldType`] of the system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldPoint {
+    pub value: Vec2,
+    pub weight: f32,
+    pub vignetting: Vignetting,
+}
+
+impl FieldPoint {
+    pub fn new(value: Vec2) -> Self {
+        Self {
+            value,
+            weight: 1.0,
+            vignetting: Vignetting::default(),
+        }
+    }
+}
+
+/// Distance from the target image point, relative to the size of the field, below which real image
+/// heights stop iterating.
+const TOLERANCE: f32 = 1e-5;
+
+/// Step used to estimate the derivatives of real image heights, relative to the size of the field.
+const STEP: f32 = 1e-3;
+
+// Implementation of field methods
+impl System {
+    /// Field of `point` as expected by [`object::ray`](crate::surface::object::ray): an angle in
+    /// degrees for objects at infinity, or a point of the object plane otherwise. Returns `None`
+    /// when the field point cannot be defined, like object heights of objects at infinity.
+    pub fn object_field(&self, point: &FieldPoint, wavelength: Wavelength) -> Option<Vec2> {
+        let distance: f32 = self.surfaces.first()?.data()[THICKNESS].into();
+
+        match self.field_type {
+            FieldType::ObjectAngle if distance.is_finite() => {
+                // Chief rays go through the center of the entrance pupil
+                let entrance = self.entrance_pupil(wavelength)?;
+                let tangent = point.value.map(|angle| angle.to_radians().tan());
+
+                Some(-tangent * (distance + entrance.position))
+            }
+            FieldType::ObjectAngle => Some(point.value),
+            FieldType::ObjectHeight => distance.is_finite().then_some(point.value),
+            FieldType::ParaxialImageHeight => self.paraxial_object_field(point.value, wavelength),
+            FieldType::RealImageHeight => {
+                let initial = self.paraxial_object_field(point.value, wavelength)?;
+                let size = point.value.length().max(1.0);
+
+                Some(newton(
+                    initial,
+                    point.value,
+                    STEP * size,
+                    TOLERANCE * size,
+                    |field| self.image_point(field, wavelength),
+                ))
+            }
+        }
+    }
+
+    /// Ray of `point` that goes through the point `pupil` of the pupil, in coordinates normalized
+    /// to its semi-diameter, after applying the vignetting factors of `point`.
+    pub fn field_ray(
+        &self,
+        point: &FieldPoint,
+        pupil: Vec2,
+        wavelength: Wavelength,
+    ) -> Option<Ray> {
+        let field = self.object_field(point, wavelength)?;
+
+        Some(self.aim(field, point.vignetting.apply(pupil), wavelength))
+    }
+
+    /// Point where the chief ray of `point` reaches the image surface, in its local coordinates.
+    /// Apertures are ignored.
+    pub fn chief_ray_image(&self, point: &FieldPoint, wavelength: Wavelength) -> Option<Vec2> {
+        self.image_point(self.object_field(point, wavelength)?, wavelength)
+    }
+
+    /// Object field whose paraxial chief ray reaches `height` on the image surface.
+    fn paraxial_object_field(&self, height: Vec2, wavelength: Wavelength) -> Option<Vec2> {
+        let x = self.paraxial_model(wavelength, Meridian::X)?;
+        let y = self.paraxial_model(wavelength, Meridian::Y)?;
+
+        Some(Vec2::new(
+            self.paraxial_object_field_meridian(&x, height.x, wavelength)?,
+            self.paraxial_object_field_meridian(&y, height.y, wavelength)?,
+        ))
+    }
+
+    fn paraxial_object_field_meridian(
+        &self,
+        model: &ParaxialModel,
+        height: f32,
+        wavelength: Wavelength,
+    ) -> Option<f32> {
+        let matrix = model.matrix();
+        let (a, b) = (matrix.x_axis.x, matrix.y_axis.x);
+        let n = model.object_index;
+        let entrance = self.entrance_pupil(wavelength)?.position;
+        let distance: f32 = self.surfaces[0].data()[THICKNESS].into();
+
+        let field = if distance.is_finite() {
+            // The chief ray leaves the object point y₀ with an angle u = -y₀ / (d + z), reaching
+            // the image at A (y₀ + d u) + B n u, which is proportional to y₀
+            let angle = -1.0 / (distance + entrance);
+            height / (a * (1.0 + distance * angle) + b * n * angle)
+        } else {
+            // Collimated chief rays with angle u cross the first surface at -z u
+            let tangent = height / (b * n - a * entrance);
+            tangent.atan().to_degrees()
+        };
+
+        field.is_finite().then_some(field)
+    }
+
+    /// Point where the chief ray of `field` reaches the image surface, in its local coordinates.
+    /// Apertures are ignored.
+    fn image_point(&self, field: Vec2, wavelength: Wavelength) -> Option<Vec2> {
+        let ray = self.aim(field, Vec2::ZERO, wavelength);
+        let trace = self.trace_until(ray, usize::MAX, false);
+
+        if !trace.is_complete() {
+            return None;
+        }
+
+        Some(trace.last()?.local_point.truncate())
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    glam::{Mat2, Vec2},
+    pupil::Pupil,
+    ray::Wavelength,
+    refracted_ray::RefractiveIndex,
+    surface::{Meridian, THICKNESS},
+    system::System,
+};
+
+/// Surface of a [`ParaxialModel`].
+#[derive(Debug, Clone, Copy)]
+pub struct ParaxialSurface {
+    /// Index of the surface in the system.
+    pub index: usize,
+    /// Transfer from the previous surface with power, coordinate breaks included.
+    pub transfer: Mat2,
+    /// Paraxial power of the surface.
+    pub power: f32,
+    /// Index of the medium after the surface, which is negative when light travels backwards.
+    pub refractive_index: RefractiveIndex,
+}
+
+impl ParaxialSurface {
+    /// Refraction matrix of the surface.
+    pub fn refraction(&self) -> Mat2 {
+        Mat2::from_cols(Vec2::new(1.0, -self.power), Vec2::Y)
+    }
+}
+
+/// Paraxial model of a system on one meridian, made of the matrices that map the height y and the
+/// reduced angle n u of a ray from one surface to the next. Surfaces refract with [[1, 0], [-P, 1]]
+/// and homogeneous media transfer with [[1, d / n], [0, 1]].
+///
+/// Rays enter the model on the first surface after the object, before being refracted by it. The
+/// last surface of the model is the image surface, which has no power.
+#[derive(Debug, Clone)]
+pub struct ParaxialModel {
+    /// Index of object space, which is negative when light travels backwards.
+    pub object_index: RefractiveIndex,
+    pub surfaces: Vec<ParaxialSurface>,
+}
+
+impl ParaxialModel {
+    /// Index of image space, which is negative when light travels backwards.
+    pub fn image_index(&self) -> RefractiveIndex {
+        match self.surfaces.len() {
+            0 | 1 => self.object_index,
+            len => self.surfaces[len - 2].refractive_index,
+        }
+    }
+
+    /// Matrix from the first surface up to the surface at `index` in the system, before its
+    /// refraction or after it when `refracted` is set. Returns `None` when the surface is not part
+    /// of the model, like coordinate breaks.
+    pub fn matrix_to(&self, index: usize, refracted: bool) -> Option<Mat2> {
+        let mut matrix = Mat2::IDENTITY;
+
+        for surface in &self.surfaces {
+            matrix = surface.transfer * matrix;
+
+            if surface.index == index {
+                return Some(if refracted {
+                    surface.refraction() * matrix
+                } else {
+                    matrix
+                });
+            }
+
+            matrix = surface.refraction() * matrix;
+        }
+
+        None
+    }
+
+    /// Matrix from the first surface to the image surface.
+    pub fn matrix(&self) -> Mat2 {
+        self.surfaces
+            .iter()
+            .fold(Mat2::IDENTITY, |matrix, surface| {
+                surface.refraction() * surface.transfer * matrix
+            })
+    }
+
+    /// Power of the system, which is minus the lower left element of [`ParaxialModel::matrix`].
+    pub fn power(&self) -> f32 {
+        -self.matrix().x_axis.y
+    }
+}
+
+/// Paraxial ray at a surface of a [`ParaxialModel`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParaxialRay {
+    /// Height of the ray on the surface.
+    pub height: f32,
+    /// Reduced angle n u of the ray before the surface.
+    pub incident: f32,
+    /// Reduced angle n u of the ray after the surface.
+    pub refracted: f32,
+}
+
+impl ParaxialModel {
+    /// Traces `ray`, given as its height and reduced angle on the first surface, returning its
+    /// state at each surface of the model.
+    pub fn trace(&self, ray: Vec2) -> Vec<ParaxialRay> {
+        let mut ray = ray;
+
+        self.surfaces
+            .iter()
+            .map(|surface| {
+                let incident = surface.transfer * ray;
+                ray = surface.refraction() * incident;
+
+                ParaxialRay {
+                    height: incident.x,
+                    incident: incident.y,
+                    refracted: ray.y,
+                }
+            })
+            .collect()
+    }
+}
+
+/// First-order properties of a system on the YZ plane.
+///
+/// Positions are measured along the local Z axis of the surface they are referred to, so they are
+/// negative for points in front of a single mirror, where light travels towards negative Z. Object
+/// space quantities are measured from the first surface and image space quantities from the last
+/// surface before the image, except for the exit pupil, which is measured from the image surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FirstOrder {
+    /// Inverse of the power of the system.
+    pub effective_focal_length: f32,
+    /// Position of the front focal point.
+    pub front_focal_length: f32,
+    /// Position of the back focal point.
+    pub back_focal_length: f32,
+    /// Positions of the front and back principal planes.
+    pub principal_planes: (f32, f32),
+    /// Positions of the front and back nodal planes.
+    pub nodal_planes: (f32, f32),
+    /// F-number of the cone of light that reaches the image, 1 / (2 n' u').
+    pub working_f_number: f32,
+    /// Numerical aperture in image space, n' sin(u').
+    pub image_numerical_aperture: f32,
+    /// Ratio between image and object heights, which is zero for objects at infinity.
+    pub magnification: f32,
+    /// Height of the chief ray of the largest field on the image surface.
+    pub image_height: f32,
+    /// Lagrange invariant of the marginal and chief rays.
+    pub lagrange_invariant: f32,
+    pub entrance_pupil: Pupil,
+    pub exit_pupil: Pupil,
+    /// Marginal ray on the first surface, as its height and reduced angle.
+    pub marginal_ray: Vec2,
+    /// Chief ray of the largest field on the first surface, as its height and reduced angle.
+    pub chief_ray: Vec2,
+}
+
+// Implementation of first-order methods
+impl System {
+    /// First-order properties of the system at `wavelength`, see [`FirstOrder`]. Returns `None`
+    /// when some surface is not supported by paraxial calculations.
+    pub fn first_order(&self, wavelength: Wavelength) -> Option<FirstOrder> {
+        let model = self.paraxial_model(wavelength, Meridian::Y)?;
+        let entrance_pupil = self.entrance_pupil(wavelength)?;
+        let exit_pupil = self.exit_pupil(wavelength)?;
+
+        let n = model.object_index;
+        let n_image = model.image_index();
+
+        // Matrix up to the last surface before the image, [[A, B], [C, D]] with C = -P
+        let to_image = model.matrix();
+        let matrix = model.surfaces.last()?.transfer.inverse() * to_image;
+        let (a, d) = (matrix.x_axis.x, matrix.y_axis.y);
+        let power = -matrix.x_axis.y;
+
+        // The marginal ray leaves the axial object point towards the edge of the entrance pupil,
+        // while the chief ray of the largest field goes through its center
+        let distance: f32 = self.surfaces[0].data()[THICKNESS].into();
+        let field = self
+            .fields
+            .iter()
+            .filter_map(|point| self.object_field(point, wavelength))
+            .map(Vec2::length)
+            .fold(0.0, f32::max);
+
+        let (marginal_ray, chief_ray) = if distance.is_finite() {
+            let marginal = entrance_pupil.semi_diameter / (distance + entrance_pupil.position);
+            let chief = -field / (distance + entrance_pupil.position);
+
+            (
+                Vec2::new(distance * marginal, n * marginal),
+                Vec2::new(field + distance * chief, n * chief),
+            )
+        } else {
+            let chief = field.to_radians().tan();
+
+            (
+                Vec2::new(entrance_pupil.semi_diameter, 0.0),
+                Vec2::new(-entrance_pupil.position * chief, n * chief),
+            )
+        };
+
+        let marginal_image = to_image * marginal_ray;
+        let chief_image = to_image * chief_ray;
+
+        let front_focal_length = -n * d / power;
+        let back_focal_length = n_image * a / power;
+
+        Some(FirstOrder {
+            effective_focal_length: power.recip(),
+            front_focal_length,
+            back_focal_length,
+            principal_planes: (
+                front_focal_length + n / power,
+                back_focal_length - n_image / power,
+            ),
+            nodal_planes: (
+                front_focal_length + n_image / power,
+                back_focal_length - n / power,
+            ),
+            working_f_number: (2.0 * marginal_image.y).abs().recip(),
+            image_numerical_aperture: n_image.abs()
+                * (marginal_image.y / n_image).atan().sin().abs(),
+            magnification: if distance.is_finite() {
+                marginal_ray.y / marginal_image.y
+            } else {
+                0.0
+            },
+            image_height: chief_image.x,
+            lagrange_invariant: chief_ray.x * marginal_ray.y - marginal_ray.x * chief_ray.y,
+            entrance_pupil,
+            exit_pupil,
+            marginal_ray,
+            chief_ray,
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    glam::{Mat2, Mat4, Vec2, Vec3},
+    intersection::Intersection,
+    material::Gradient,
+    ray::Ray,
+    refracted_ray::{RefractedRay, RefractiveIndex},
+    surface::Placement,
+};
+
+/// Maximum number of integration steps between two surfaces.
+const MAX_STEPS: usize = 1 << 16;
+
+/// Gradient-index medium between two surfaces.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Medium<'g> {
+    gradient: &'g Gradient,
+    /// Index on the vertex at the traced wavelength.
+    base: RefractiveIndex,
+    /// Transformation from the coordinate system of the surface right before the medium.
+    inverse: Mat4,
+}
+
+impl<'g> Medium<'g> {
+    /// Medium that starts at the surface placed at `placement`.
+    pub fn new(gradient: &'g Gradient, base: RefractiveIndex, placement: &Placement) -> Self {
+        Self {
+            gradient,
+            base,
+            inverse: placement.inverse,
+        }
+    }
+
+    /// Refractive index at the global `point`.
+    pub fn refractive_index(&self, point: Vec3) -> RefractiveIndex {
+        self.gradient
+            .refractive_index(self.base, self.inverse.transform_point3(point))
+    }
+
+    /// Right-hand side of the ray equation d²r/dt² = n ∇n, where dt = ds / n.
+    fn curvature(&self, point: Vec3) -> Vec3 {
+        let (n, gradient) = self
+            .gradient
+            .refractive_index_and_gradient(self.base, self.inverse.transform_point3(point));
+
+        // The inverse of a rigid transformation maps gradients back with its transpose
+        n * self.inverse.transpose().transform_vector3(gradient)
+    }
+
+    /// Propagates `ray` along its curved path with the Runge-Kutta method of Sharma et al., until
+    /// the straight line to the next surface is shorter than a step. `intersect` gives the
+    /// intersection of a ray with that surface.
+    ///
+    /// Returns the ray right before the next surface, in the local refractive index, and the
+    /// optical path it traveled.
+    pub fn propagate(
+        &self,
+        ray: &RefractedRay,
+        intersect: impl Fn(&RefractedRay) -> Option<Intersection>,
+    ) -> Option<(RefractedRay, f32)> {
+        let step = self.gradient.step();
+
+        let mut position = ray.origin;
+        let mut n = self.refractive_index(position);
+        // Optical direction vector n dr/ds, which is also dr/dt
+        let mut optical_direction = ray.direction.normalize() * n;
+        let mut optical_path = 0.0;
+
+        for _ in 0..MAX_STEPS {
+            let current = RefractedRay::new(
+                Ray::new(position, optical_direction.normalize(), ray.wavelength),
+                n,
+            );
+
+            let (length, last) = match intersect(&current) {
+                Some(intersection) if intersection.t <= step => (intersection.t.max(0.0), true),
+                _ => (step, false),
+            };
+
+            let dt = length / n;
+
+            let a = dt * self.curvature(position);
+            let middle = position + 0.5 * dt * optical_direction + dt / 8.0 * a;
+            let b = dt * self.curvature(middle);
+            let c = dt * self.curvature(position + dt * optical_direction + 0.5 * dt * b);
+
+            let next = position + dt * (optical_direction + (a + 2.0 * b) / 6.0);
+            let next_n = self.refractive_index(next);
+
+            // Optical path is the integral of n ds = n² dt, using Simpson's rule
+            let middle_n = self.refractive_index(middle);
+            optical_path += dt * (n * n + 4.0 * middle_n * middle_n + next_n * next_n) / 6.0;
+
+            optical_direction += (a + 4.0 * b + c) / 6.0;
+            position = next;
+            n = next_n;
+
+            if !position.is_finite() {
+                return None;
+            }
+
+            if last {
+                return Some((
+                    RefractedRay::new(
+                        Ray::new(position, optical_direction.normalize(), ray.wavelength),
+                        n,
+                    ),
+                    optical_path,
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+impl Gradient {
+    /// Paraxial transfer matrix of a slab of the material with the given `thickness`, acting on
+    /// the ray height and its reduced angle n u. `base` is the index on the vertex.
+    pub fn paraxial_transfer(&self, base: RefractiveIndex, thickness: f32) -> Mat2 {
+        let steps = (thickness.abs() / self.step()).ceil().max(1.0);
+        let dz = thickness / steps;
+
+        // Near the axis n = n(0, z) + n_r2 r², so d(n u)/dz = 2 n_r2 y
+        let derivative = |z: f32, ray: Vec2| {
+            let n = self.refractive_index(base, Vec3::new(0.0, 0.0, z));
+            Vec2::new(ray.y / n, 2.0 * self.radial[0] * ray.x)
+        };
+
+        // Integrates the rays that start with unit height and unit angle, which give the columns
+        // of the matrix
+        let mut columns = [Vec2::X, Vec2::Y];
+
+        for ray in &mut columns {
+            for i in 0..steps as usize {
+                let z = i as f32 * dz;
+
+                let k1 = derivative(z, *ray);
+                let k2 = derivative(z + 0.5 * dz, *ray + 0.5 * dz * k1);
+                let k3 = derivative(z + 0.5 * dz, *ray + 0.5 * dz * k2);
+                let k4 = derivative(z + dz, *ray + dz * k3);
+
+                *ray += dz * (k1 + 2.0 * (k2 + k3) + k4) / 6.0;
+            }
+        }
+
+        Mat2::from_cols(columns[0], columns[1])
+    }
+}
@@ -1,6 +1,6 @@
 use crate::glam::Vec3;
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Intersection {
     pub normal: Vec3,
     pub t: f32,
@@ -1,9 +1,23 @@
+pub mod diffraction;
+pub mod fans;
+mod fft;
+pub mod fields;
+pub mod first_order;
+mod grin;
 pub mod intersection;
 pub mod material;
+pub mod opd;
+pub mod pupil;
 pub mod ray;
 pub mod refracted_ray;
+pub mod seidel;
+mod solver;
+pub mod spot;
 pub mod surface;
 pub mod system;
+pub mod trace;
+pub mod wavelengths;
+pub mod zernike;
 
 // CPU specific implementations
 #[cfg(not(target_arch = "spirv"))]
@@ -24,5 +38,6 @@ pub mod prelude {
         ray::{Ray, Wavelength},
         surface::Surface,
         system::System,
+        trace::Trace,
     };
 }
@@ -1,10 +1,13 @@
 use std::num::NonZeroU32;
 
-use crate::ray::Wavelength;
+use crate::{
+    glam::{Vec3, Vec3Swizzles},
+    ray::Wavelength,
+};
 
 pub type MaterialIndex = NonZeroU32;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub enum Formula {
     Constant {
@@ -140,21 +143,93 @@ impl Formula {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Index profile of a gradient-index (GRIN) material,
+/// n(r, z, λ) = n₀(λ) + n_r2 r² + n_r4 r⁴ + n_r6 r⁶ + n_z1 z + n_z2 z² + n_z3 z³,
+/// where n₀ is given by the formula of the material. r and z are measured in the coordinate system
+/// of the surface right before the point, so the profile restarts at every surface.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gradient {
+    /// Coefficients of r², r⁴ and r⁶.
+    pub radial: [f32; 3],
+    /// Coefficients of z, z² and z³.
+    pub axial: [f32; 3],
+    /// Length of each integration step used to trace rays through the material.
+    pub step: f32,
+}
+
+impl Gradient {
+    /// Step used when [`Gradient::step`] is not positive.
+    pub const DEFAULT_STEP: f32 = 0.1;
+
+    pub const fn step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            Self::DEFAULT_STEP
+        }
+    }
+
+    /// Refractive index at the local `point`, where `base` is n₀ at the traced wavelength.
+    pub fn refractive_index(&self, base: f32, point: Vec3) -> f32 {
+        self.refractive_index_and_gradient(base, point).0
+    }
+
+    /// Refractive index at the local `point` and its gradient.
+    pub fn refractive_index_and_gradient(&self, base: f32, point: Vec3) -> (f32, Vec3) {
+        let r2 = point.xy().length_squared();
+        let z = point.z;
+
+        let [r2_coefficient, r4_coefficient, r6_coefficient] = self.radial;
+        let [z1_coefficient, z2_coefficient, z3_coefficient] = self.axial;
+
+        let radial = r2 * (r2_coefficient + r2 * (r4_coefficient + r2 * r6_coefficient));
+        let axial = z * (z1_coefficient + z * (z2_coefficient + z * z3_coefficient));
+
+        // Derivative of the radial part with respect to r, divided by r
+        let radial_slope =
+            2.0 * r2_coefficient + r2 * (4.0 * r4_coefficient + 6.0 * r2 * r6_coefficient);
+        let axial_slope = z1_coefficient + z * (2.0 * z2_coefficient + 3.0 * z * z3_coefficient);
+
+        (
+            base + radial + axial,
+            (point.xy() * radial_slope).extend(axial_slope),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Material {
     name: String,
     formula: Formula,
+    gradient: Option<Gradient>,
 }
 
 impl Material {
     pub const fn new(name: String, formula: Formula) -> Self {
-        Material { name, formula }
+        Material {
+            name,
+            formula,
+            gradient: None,
+        }
     }
 
+    /// Turns the material into a GRIN material, whose index on the axis is given by its formula.
+    pub fn with_gradient(self, gradient: Gradient) -> Self {
+        Material {
+            gradient: Some(gradient),
+            ..self
+        }
+    }
+
+    /// Refractive index of the material. For GRIN materials, this is the index at the vertex.
     pub fn refractive_index(&self, wavelength: Wavelength) -> f32 {
         self.formula.compute(wavelength)
     }
 
+    pub const fn gradient(&self) -> Option<&Gradient> {
+        self.gradient.as_ref()
+    }
+
     pub const fn name(&self) -> &str {
         self.name.as_str()
     }
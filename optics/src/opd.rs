@@ -0,0 +1,352 @@
+use rayon::prelude::*;
+
+use crate::{
+    fields::FieldPoint,
+    glam::{Vec2, Vec3},
+    pupil::pupil_line,
+    ray::Wavelength,
+    system::System,
+    trace::TraceRecord,
+    zernike::{ZernikeFit, ZernikeOrdering},
+};
+
+/// Sphere that optical path differences are measured on. It is centered on the point where the
+/// chief ray of the primary wavelength reaches the image, and goes through the center of the
+/// paraxial exit pupil.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReferenceSphere {
+    pub(crate) center: Vec3,
+    /// Center of the exit pupil, which is `None` for telecentric image spaces. In that case, the
+    /// reference is the plane through `center` perpendicular to the chief ray.
+    pub(crate) pupil: Option<Vec3>,
+    /// Direction of the chief ray on the image.
+    axis: Vec3,
+    /// Optical path of the chief ray of the wavelength up to the sphere.
+    chief_path: f64,
+    wavelength: Wavelength,
+}
+
+impl ReferenceSphere {
+    /// Distance along the ray that reached the image at `record`, from the image back to the
+    /// sphere. It is computed in double precision, like optical paths.
+    fn distance(&self, record: &TraceRecord) -> f64 {
+        let direction = record.direction.as_dvec3().normalize();
+        let offset = record.point.as_dvec3() - self.center.as_dvec3();
+
+        match self.pupil {
+            Some(pupil) => {
+                let radius = pupil.as_dvec3() - self.center.as_dvec3();
+                let b = offset.dot(direction);
+                let discriminant =
+                    (b * b - offset.length_squared() + radius.length_squared()).max(0.0);
+
+                // The sphere is crossed on the side of the exit pupil
+                if radius.dot(direction) < 0.0 {
+                    -b - discriminant.sqrt()
+                } else {
+                    -b + discriminant.sqrt()
+                }
+            }
+            None => {
+                let axis = self.axis.as_dvec3();
+                -offset.dot(axis) / direction.dot(axis)
+            }
+        }
+    }
+
+    /// Point where the ray that reached the image at `record` crosses the sphere.
+    pub(crate) fn crossing(&self, record: &TraceRecord) -> Vec3 {
+        record.point + record.direction.normalize() * self.distance(record) as f32
+    }
+
+    /// Optical path from the object up to the sphere of the ray that reached the image at
+    /// `record`.
+    fn optical_path(&self, record: &TraceRecord) -> f64 {
+        record.optical_path + f64::from(record.refractive_index) * self.distance(record)
+    }
+
+    /// Optical path difference of the ray that reached the image at `record`, in waves. Positive
+    /// values mean that the ray is ahead of the chief ray.
+    pub(crate) fn opd(&self, record: &TraceRecord) -> f32 {
+        // Wavelengths are in micrometers and lengths in millimeters
+        ((self.chief_path - self.optical_path(record)) / (f64::from(self.wavelength) * 1e-3)) as f32
+    }
+}
+
+/// Optical path differences of a single wavelength across the pupil of a field point, in waves.
+///
+/// Each fan holds points made of a normalized pupil coordinate and the optical path difference of
+/// the ray that goes through it. Vignetted rays are left out.
+#[derive(Debug, Clone)]
+pub struct OpdFan {
+    pub wavelength: Wavelength,
+    /// Rays on the YZ plane of the pupil, as (Py, W).
+    pub tangential: Vec<Vec2>,
+    /// Rays on the XZ plane of the pupil, as (Px, W).
+    pub sagittal: Vec<Vec2>,
+}
+
+/// Optical path differences over the exit pupil of a field point, in waves.
+#[derive(Debug, Clone)]
+pub struct Wavefront {
+    pub wavelength: Wavelength,
+    /// Number of samples on each side of the map.
+    pub size: usize,
+    /// Samples of the map on a square grid over the pupil, row by row from Py = -1 to Py = 1, see
+    /// [`pupil_line`]. Points outside the pupil and vignetted rays have no value.
+    pub values: Vec<Option<f32>>,
+    /// Difference between the largest and smallest values.
+    pub peak_to_valley: f32,
+    /// Standard deviation of the values, with the mean removed.
+    pub rms: f32,
+    /// Strehl ratio from the Maréchal approximation, exp(-(2π RMS)²).
+    pub strehl: f32,
+}
+
+impl Wavefront {
+    /// Normalized pupil coordinates of each sample of the map.
+    pub fn pupil_points(&self) -> impl Iterator<Item = Vec2> {
+        pupil_line(self.size).flat_map(move |y| pupil_line(self.size).map(move |x| Vec2::new(x, y)))
+    }
+
+    /// Least-squares fit of the first `terms` terms of `ordering` to the map, see [`ZernikeFit`].
+    /// Coefficients are in waves.
+    pub fn zernike_fit(&self, ordering: ZernikeOrdering, terms: usize) -> Option<ZernikeFit> {
+        let samples = self
+            .pupil_points()
+            .zip(&self.values)
+            .filter_map(|(point, value)| Some((point, (*value)?)));
+
+        ZernikeFit::new(ordering, terms, samples)
+    }
+}
+
+// Implementation of optical path difference methods
+impl System {
+    /// Reference sphere of `point` at `wavelength`, or `None` when the chief ray cannot be traced.
+    pub(crate) fn reference_sphere(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+    ) -> Option<ReferenceSphere> {
+        let primary = self.primary_wavelength();
+
+        let chief = |wavelength| {
+            let field = self.object_field(point, wavelength)?;
+            let trace =
+                self.trace_until(self.aim(field, Vec2::ZERO, wavelength), usize::MAX, false);
+
+            if !trace.is_complete() {
+                return None;
+            }
+
+            trace.last().copied()
+        };
+
+        let primary_chief = chief(primary)?;
+        let center = primary_chief.point;
+        let axis = primary_chief.direction.normalize();
+
+        // The exit pupil is measured from the image surface along its local Z axis
+        let transform = self.image_transform();
+        let pupil = self
+            .exit_pupil(primary)
+            .map(|pupil| pupil.position)
+            .filter(|position| position.is_finite())
+            .map(|position| transform.transform_point3(Vec3::Z * position));
+
+        let mut sphere = ReferenceSphere {
+            center,
+            pupil,
+            axis,
+            chief_path: 0.0,
+            wavelength,
+        };
+
+        sphere.chief_path = sphere.optical_path(&chief(wavelength)?);
+
+        Some(sphere)
+    }
+
+    /// Optical path difference of the ray of `point` through `pupil` against `sphere`, in waves.
+    fn opd_with(
+        &self,
+        point: &FieldPoint,
+        field: Vec2,
+        pupil: Vec2,
+        sphere: &ReferenceSphere,
+    ) -> Option<f32> {
+        let wavelength = sphere.wavelength;
+        let trace = self.trace(self.aim(field, point.vignetting.apply(pupil), wavelength));
+
+        if !trace.is_complete() {
+            return None;
+        }
+
+        Some(sphere.opd(trace.last()?))
+    }
+
+    /// Tangential and sagittal optical path difference fans of `point` for every wavelength, each
+    /// with `samples` rays. Returns `None` when the field point cannot be defined or its chief ray
+    /// cannot be traced.
+    pub fn opd_fans(&self, point: &FieldPoint, samples: usize) -> Option<Vec<OpdFan>> {
+        let coordinates: Vec<_> = pupil_line(samples).collect();
+
+        self.wavelengths
+            .iter()
+            .map(|wavelength| {
+                let wavelength = wavelength.value;
+                let field = self.object_field(point, wavelength)?;
+                let sphere = self.reference_sphere(point, wavelength)?;
+
+                let fan = |pupil: fn(f32) -> Vec2| -> Vec<Vec2> {
+                    coordinates
+                        .par_iter()
+                        .filter_map(|&p| {
+                            Some(Vec2::new(
+                                p,
+                                self.opd_with(point, field, pupil(p), &sphere)?,
+                            ))
+                        })
+                        .collect()
+                };
+
+                Some(OpdFan {
+                    wavelength,
+                    tangential: fan(|p| Vec2::new(0.0, p)),
+                    sagittal: fan(|p| Vec2::new(p, 0.0)),
+                })
+            })
+            .collect()
+    }
+
+    /// Wavefront map of `point` at `wavelength`, sampled on a grid of `size` by `size` points over
+    /// the pupil. Returns `None` when the field point cannot be defined or its chief ray cannot be
+    /// traced.
+    pub fn wavefront(
+        &self,
+        point: &FieldPoint,
+        wavelength: Wavelength,
+        size: usize,
+    ) -> Option<Wavefront> {
+        let field = self.object_field(point, wavelength)?;
+        let sphere = self.reference_sphere(point, wavelength)?;
+
+        let mut wavefront = Wavefront {
+            wavelength,
+            size,
+            values: Vec::new(),
+            peak_to_valley: 0.0,
+            rms: 0.0,
+            strehl: 1.0,
+        };
+
+        let points: Vec<_> = wavefront.pupil_points().collect();
+        wavefront.values = points
+            .par_iter()
+            .map(|&pupil| {
+                (pupil.length_squared() <= 1.0 + 1e-6)
+                    .then(|| self.opd_with(point, field, pupil, &sphere))
+                    .flatten()
+            })
+            .collect();
+
+        let values: Vec<_> = wavefront.values.iter().flatten().copied().collect();
+
+        if !values.is_empty() {
+            let count = values.len() as f32;
+            let mean = values.iter().sum::<f32>() / count;
+            let variance = values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f32>()
+                / count;
+            let (min, max) = values
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+                    (min.min(value), max.max(value))
+                });
+
+            wavefront.peak_to_valley = max - min;
+            wavefront.rms = variance.sqrt();
+            wavefront.strehl = (-(std::f32::consts::TAU * wavefront.rms).powi(2)).exp();
+        }
+
+        Some(wavefront)
+    }
+
+    /// Zernike fits of the wavefront maps of every field point, each holding one fit per
+    /// wavelength of the system, see [`System::wavefront`] and [`Wavefront::zernike_fit`]. Fits
+    /// are `None` when the field point cannot be traced.
+    pub fn zernike_fits(
+        &self,
+        ordering: ZernikeOrdering,
+        terms: usize,
+        size: usize,
+    ) -> Vec<Vec<Option<ZernikeFit>>> {
+        self.fields
+            .iter()
+            .map(|point| {
+                self.wavelengths
+                    .iter()
+                    .map(|wavelength| {
+                        self.wavefront(point, wavelength.value, size)?
+                            .zernike_fit(ordering, terms)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::FieldType,
+        surface::{
+            CONIC, CURVATURE, MIRROR, SEMI_DIAMETER, Surface, SurfaceData, SurfaceKind, THICKNESS,
+        },
+    };
+
+    /// A paraboloid focuses collimated light on axis without aberrations, so any optical path
+    /// difference comes from the precision of the trace.
+    #[test]
+    fn paraboloid_has_no_opd() {
+        let system = System {
+            surfaces: vec![
+                Surface::new(
+                    SurfaceKind::Object,
+                    SurfaceData::default().with(THICKNESS, f32::INFINITY),
+                ),
+                Surface::new(
+                    SurfaceKind::Spherical,
+                    SurfaceData::default()
+                        .with(THICKNESS, -100.0)
+                        .with(CURVATURE, -1.0 / 200.0)
+                        .with(CONIC, -1.0)
+                        .with(SEMI_DIAMETER, 10.0)
+                        .with(MIRROR, 1u32),
+                ),
+                Surface::new(SurfaceKind::Image, SurfaceData::default()),
+            ],
+            field_type: FieldType::ObjectAngle,
+            fields: vec![FieldPoint::new(Vec2::ZERO)],
+            ..System::default()
+        };
+        let point = &system.fields[0];
+
+        for fan in system.opd_fans(point, 33).unwrap() {
+            for opd in fan.tangential.iter().chain(&fan.sagittal) {
+                assert!(opd.y.abs() < 1e-3, "OPD of {} waves at {}", opd.y, opd.x);
+            }
+        }
+
+        let wavefront = system
+            .wavefront(point, system.primary_wavelength(), 33)
+            .unwrap();
+
+        assert!(wavefront.values.iter().flatten().count() > 700);
+        assert!(wavefront.peak_to_valley < 1e-3);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    first_order::ParaxialModel,
+    glam::Vec2,
+    ray::{Ray, Wavelength},
+    solver::newton,
+    surface::{Meridian, SEMI_DIAMETER, THICKNESS, object},
+    system::System,
+};
+
+/// Paraxial image of the aperture stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pupil {
+    /// Position of the pupil along the local Z axis of the surface it is measured from, which is
+    /// the first surface for entrance pupils and the image surface for exit pupils. Light travels
+    /// towards negative Z after an odd number of mirrors. Telecentric spaces have pupils at
+    /// infinity.
+    pub position: f32,
+    /// Semi-diameter of the pupil, which is infinite when the pupil is at infinity.
+    pub semi_diameter: f32,
+}
+
+/// How rays of a given field are aimed to reach a point of the pupil.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RayAiming {
+    /// Rays are aimed at the paraxial entrance pupil.
+    Off,
+    /// Rays are aimed at the stop using the paraxial model of the system, which is exact for
+    /// systems without pupil aberrations.
+    #[default]
+    Paraxial,
+    /// Rays are aimed at the stop by tracing real rays, starting from paraxial aiming.
+    Real,
+}
+
+impl RayAiming {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Paraxial, Self::Real];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Paraxial => "Paraxial",
+            Self::Real => "Real",
+        }
+    }
+}
+
+/// Distance to the target point on the stop, relative to its semi-diameter, below which real ray
+/// aiming stops iterating.
+const TOLERANCE: f32 = 1e-5;
+
+/// Step used to estimate the derivatives of real ray aiming, relative to the stop semi-diameter.
+const STEP: f32 = 1e-3;
+
+// Implementation of pupil and ray aiming methods
+impl System {
+    /// Index of the aperture stop, which is never the object nor the image surface.
+    pub fn stop(&self) -> usize {
+        (self.stop_index as usize).clamp(1, self.surfaces.len().saturating_sub(2).max(1))
+    }
+
+    /// Semi-diameter of the aperture stop.
+    pub fn stop_semi_diameter(&self) -> f32 {
+        self.surfaces
+            .get(self.stop())
+            .map_or(0.0, |stop| stop.data()[SEMI_DIAMETER].into())
+    }
+
+    /// Paraxial image of the stop in object space, on the YZ plane.
+    ///
+    /// When `[[A, B], [C, D]]` maps rays from the first surface to the stop, a ray that crosses
+    /// the axis on the stop crosses it at n B / A in object space, where the stop is magnified by
+    /// 1 / A.
+    pub fn entrance_pupil(&self, wavelength: Wavelength) -> Option<Pupil> {
+        let model = self.paraxial_model(wavelength, Meridian::Y)?;
+        let matrix = model.matrix_to(self.stop(), false)?;
+
+        Some(Pupil {
+            position: model.object_index * matrix.y_axis.x / matrix.x_axis.x,
+            semi_diameter: (self.stop_semi_diameter() / matrix.x_axis.x).abs(),
+        })
+    }
+
+    /// Paraxial image of the stop in image space, on the YZ plane.
+    ///
+    /// When `[[A, B], [C, D]]` maps rays from the stop to the image surface, a ray that crosses
+    /// the axis on the stop crosses it at -n' B / D in image space, where the stop is magnified by
+    /// A + C z / n'.
+    pub fn exit_pupil(&self, wavelength: Wavelength) -> Option<Pupil> {
+        let model = self.paraxial_model(wavelength, Meridian::Y)?;
+        let matrix = model.matrix() * model.matrix_to(self.stop(), true)?.inverse();
+
+        let n = model.image_index();
+        let position = -n * matrix.y_axis.x / matrix.y_axis.y;
+        let magnification = if position.is_finite() {
+            matrix.x_axis.x + matrix.x_axis.y * position / n
+        } else {
+            f32::INFINITY
+        };
+
+        Some(Pupil {
+            position,
+            semi_diameter: (self.stop_semi_diameter() * magnification).abs(),
+        })
+    }
+
+    /// Ray of `field` that goes through the point `pupil` of the pupil, in coordinates normalized
+    /// to its semi-diameter, following [`System::ray_aiming`].
+    ///
+    /// See [`object::ray`] for the meaning of `field`.
+    pub fn aim(&self, field: Vec2, pupil: Vec2, wavelength: Wavelength) -> Ray {
+        let object = &self.surfaces[0];
+
+        let paraxial = match self.ray_aiming {
+            RayAiming::Off => None,
+            RayAiming::Paraxial | RayAiming::Real => self.paraxial_aim(field, pupil, wavelength),
+        };
+
+        let Some(target) = paraxial else {
+            let target = match self.entrance_pupil(wavelength) {
+                Some(entrance) if entrance.position.is_finite() => {
+                    (pupil * entrance.semi_diameter).extend(entrance.position)
+                }
+                _ => (pupil * self.stop_semi_diameter()).extend(0.0),
+            };
+
+            return object::ray(&object.data, field, target, wavelength);
+        };
+
+        if self.ray_aiming != RayAiming::Real {
+            return object::ray(&object.data, field, target.extend(0.0), wavelength);
+        }
+
+        // Newton iterations on the point of the first surface plane the ray is aimed at
+        let semi_diameter = self.stop_semi_diameter().max(f32::EPSILON);
+        let target = newton(
+            target,
+            pupil * semi_diameter,
+            STEP * semi_diameter,
+            TOLERANCE * semi_diameter,
+            |target| self.stop_point(field, target, wavelength),
+        );
+
+        object::ray(&object.data, field, target.extend(0.0), wavelength)
+    }
+
+    /// Point of the first surface plane that rays of `field` must be aimed at to reach the point
+    /// `pupil` of the stop, according to the paraxial model of each meridian.
+    fn paraxial_aim(&self, field: Vec2, pupil: Vec2, wavelength: Wavelength) -> Option<Vec2> {
+        let x = self.paraxial_model(wavelength, Meridian::X)?;
+        let y = self.paraxial_model(wavelength, Meridian::Y)?;
+        let stop = pupil * self.stop_semi_diameter();
+
+        Some(Vec2::new(
+            self.paraxial_aim_meridian(&x, field.x, stop.x)?,
+            self.paraxial_aim_meridian(&y, field.y, stop.y)?,
+        ))
+    }
+
+    /// Height on the first surface of the paraxial ray of `field` that reaches `height` on the
+    /// stop.
+    fn paraxial_aim_meridian(&self, model: &ParaxialModel, field: f32, height: f32) -> Option<f32> {
+        let matrix = model.matrix_to(self.stop(), false)?;
+        let (a, b) = (matrix.x_axis.x, matrix.y_axis.x);
+        let n = model.object_index;
+        let distance: f32 = self.surfaces[0].data()[THICKNESS].into();
+
+        let aim = if distance.is_finite() {
+            // Rays leave the object point y₀ with an angle u, reaching the stop at
+            // A (y₀ + d u) + B n u
+            let angle = (height - a * field) / (a * distance + b * n);
+            field + distance * angle
+        } else {
+            // Collimated rays have a fixed angle
+            let angle = field.to_radians().tan();
+            (height - b * n * angle) / a
+        };
+
+        aim.is_finite().then_some(aim)
+    }
+
+    /// Point where the ray of `field` aimed at `target`, on the first surface plane, reaches the
+    /// stop, in its local coordinates. Apertures are ignored.
+    fn stop_point(&self, field: Vec2, target: Vec2, wavelength: Wavelength) -> Option<Vec2> {
+        let stop = self.stop();
+        let ray = object::ray(
+            &self.surfaces[0].data,
+            field,
+            target.extend(0.0),
+            wavelength,
+        );
+        let trace = self.trace_until(ray, stop, false);
+
+        Some(trace.records.get(stop)?.local_point.truncate())
+    }
+}
+
+/// Normalized pupil coordinates of `samples` points evenly spread from -1 to 1, or a single point
+/// at the center.
+pub fn pupil_line(samples: usize) -> impl Iterator<Item = f32> {
+    (0..samples).map(move |i| match samples {
+        1 => 0.0,
+        _ => 2.0 * i as f32 / (samples - 1) as f32 - 1.0,
+    })
+}
+
+/// Points of a square grid of `size` by `size` points over the unit circle, in normalized pupil
+/// coordinates, see [`pupil_line`]. Points outside the circle are left out.
+pub fn pupil_grid(size: usize) -> impl Iterator<Item = Vec2> {
+    pupil_line(size)
+        .flat_map(move |y| pupil_line(size).map(move |x| Vec2::new(x, y)))
+        .filter(|point| point.length_squared() <= 1.0 + 1e-6)
+}
@@ -1,7 +1,33 @@
-use crate::ray::Ray;
+use crate::{glam::Vec3, intersection::Intersection, ray::Ray};
 
 pub type RefractiveIndex = f32;
 
+/// Reason why a ray could not continue through a surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RayFailure {
+    /// The ray does not intersect the surface.
+    Miss,
+    /// The ray is totally internally reflected by the surface.
+    TotalInternalReflection,
+    /// The selected diffraction order does not propagate for the ray.
+    EvanescentOrder,
+    /// The ray is blocked by an aperture or obscuration of the surface.
+    Vignetted,
+}
+
+#[cfg(not(target_arch = "spirv"))]
+impl RayFailure {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Miss => "Miss",
+            Self::TotalInternalReflection => "Total internal reflection",
+            Self::EvanescentOrder => "Evanescent diffraction order",
+            Self::Vignetted => "Vignetted",
+        }
+    }
+}
+
 #[derive(Clone, encase::ShaderType)]
 pub struct RefractedRay {
     pub ray: Ray,
@@ -15,6 +41,85 @@ impl RefractedRay {
             refractive_index,
         }
     }
+
+    /// Refracts the ray at `intersection` into a medium of index `refractive_index`, using the
+    /// vector form of Snell's law. The resulting ray starts at the intersection point.
+    pub fn refract(
+        &self,
+        intersection: &Intersection,
+        refractive_index: RefractiveIndex,
+    ) -> Result<Self, RayFailure> {
+        let normal = facing_normal(self.direction, intersection.normal);
+        let eta = self.refractive_index / refractive_index;
+
+        let cos_i = -self.direction.dot(normal);
+        let k = 1.0 - eta * eta * (1.0 - cos_i * cos_i);
+
+        if k < 0.0 {
+            return Err(RayFailure::TotalInternalReflection);
+        }
+
+        let direction = (eta * self.direction + (eta * cos_i - k.sqrt()) * normal).normalize();
+
+        Ok(Self::new(
+            Ray::new(self.at(intersection.t), direction, self.wavelength),
+            refractive_index,
+        ))
+    }
+
+    /// Reflects the ray at `intersection`. The resulting ray starts at the intersection point and
+    /// stays in the same medium.
+    pub fn reflect(&self, intersection: &Intersection) -> Self {
+        let normal = facing_normal(self.direction, intersection.normal);
+        let direction = self.direction - 2.0 * self.direction.dot(normal) * normal;
+
+        Self::new(
+            Ray::new(self.at(intersection.t), direction, self.wavelength),
+            self.refractive_index,
+        )
+    }
+
+    /// Diffracts the ray at `intersection` by a phase surface, using the generalized grating
+    /// equation n' t' = n t + ∇Φ, where t and t' are the components of the ray directions tangent
+    /// to the surface and ∇Φ is the gradient of the optical path added by the surface, given in
+    /// global coordinates. Reflective surfaces send the ray back into the same medium.
+    pub fn diffract(
+        &self,
+        intersection: &Intersection,
+        refractive_index: RefractiveIndex,
+        phase_gradient: Vec3,
+        reflective: bool,
+    ) -> Result<Self, RayFailure> {
+        let normal = facing_normal(self.direction, intersection.normal);
+
+        // Only the part of the gradient that lies on the tangent plane deflects the ray
+        let phase_gradient = phase_gradient - phase_gradient.dot(normal) * normal;
+
+        let tangential = self.refractive_index
+            * (self.direction - self.direction.dot(normal) * normal)
+            + phase_gradient;
+
+        let normal_squared = refractive_index * refractive_index - tangential.length_squared();
+
+        if normal_squared < 0.0 {
+            return Err(RayFailure::EvanescentOrder);
+        }
+
+        // The normal faces the incoming ray, so reflected rays leave along it and transmitted rays
+        // leave against it
+        let normal_component = if reflective {
+            normal_squared.sqrt()
+        } else {
+            -normal_squared.sqrt()
+        };
+
+        let direction = ((tangential + normal_component * normal) / refractive_index).normalize();
+
+        Ok(Self::new(
+            Ray::new(self.at(intersection.t), direction, self.wavelength),
+            refractive_index,
+        ))
+    }
 }
 
 impl core::ops::Deref for RefractedRay {
@@ -24,3 +129,13 @@ impl core::ops::Deref for RefractedRay {
         &self.ray
     }
 }
+
+/// Surface normals may point to either side of the surface, this flips `normal` so that it faces
+/// the incoming `direction`.
+fn facing_normal(direction: Vec3, normal: Vec3) -> Vec3 {
+    if direction.dot(normal) > 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
@@ -0,0 +1,212 @@
+use std::{iter::Sum, ops::Add};
+
+use crate::{
+    first_order::ParaxialModel,
+    surface::{CONIC, CURVATURE, Meridian, Surface, SurfaceKind, binary2, even_asphere},
+    system::System,
+};
+
+/// Third-order aberration coefficients, in the same units as lengths. At the edge of the pupil and
+/// field, the wavefront aberration is S_I / 8 for spherical aberration, S_II / 2 for coma,
+/// S_III / 2 for astigmatism, (S_III + S_IV) / 4 for field curvature, S_V / 2 for distortion,
+/// C_L / 2 for axial color and C_T for lateral color.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SeidelCoefficients {
+    /// Spherical aberration, S_I.
+    pub spherical: f32,
+    /// Coma, S_II.
+    pub coma: f32,
+    /// Astigmatism, S_III.
+    pub astigmatism: f32,
+    /// Petzval field curvature, S_IV.
+    pub petzval: f32,
+    /// Distortion, S_V.
+    pub distortion: f32,
+    /// Axial color, C_L.
+    pub axial_color: f32,
+    /// Lateral color, C_T.
+    pub lateral_color: f32,
+}
+
+impl SeidelCoefficients {
+    pub const NAMES: [&str; 7] = ["S1", "S2", "S3", "S4", "S5", "CL", "CT"];
+
+    /// Coefficients in the order of [`SeidelCoefficients::NAMES`].
+    pub const fn to_array(&self) -> [f32; 7] {
+        [
+            self.spherical,
+            self.coma,
+            self.astigmatism,
+            self.petzval,
+            self.distortion,
+            self.axial_color,
+            self.lateral_color,
+        ]
+    }
+}
+
+impl Add for SeidelCoefficients {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            spherical: self.spherical + rhs.spherical,
+            coma: self.coma + rhs.coma,
+            astigmatism: self.astigmatism + rhs.astigmatism,
+            petzval: self.petzval + rhs.petzval,
+            distortion: self.distortion + rhs.distortion,
+            axial_color: self.axial_color + rhs.axial_color,
+            lateral_color: self.lateral_color + rhs.lateral_color,
+        }
+    }
+}
+
+impl Sum for SeidelCoefficients {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Contribution of each surface to the third-order aberrations of a system.
+#[derive(Debug, Clone, Default)]
+pub struct Seidel {
+    /// Index of each surface in the system, along with its coefficients.
+    pub surfaces: Vec<(usize, SeidelCoefficients)>,
+    pub total: SeidelCoefficients,
+}
+
+/// Coefficient of r⁴ in the difference between the sag of `surface` and the sphere of its paraxial
+/// curvature. Only rotationally symmetric terms are taken into account.
+fn fourth_order_sag(surface: &Surface) -> f32 {
+    match surface.kind() {
+        SurfaceKind::EvenAsphere => even_asphere::fourth_order(surface.data()),
+        kind if kind.fields()[CONIC].is_some() => {
+            let curvature: f32 = surface.data()[CURVATURE].into();
+            let conic: f32 = surface.data()[CONIC].into();
+
+            conic * curvature.powi(3) / 8.0
+        }
+        _ => 0.0,
+    }
+}
+
+// Implementation of third-order aberration methods
+impl System {
+    /// Seidel coefficients of the system on the YZ plane, computed from the paraxial marginal and
+    /// chief rays at the primary wavelength, see [`System::first_order`]. Axial and lateral color
+    /// are taken between the shortest and longest wavelengths of the system.
+    ///
+    /// Paraxial surfaces are ideal thin lenses, and diffractive surfaces behave like their
+    /// substrate, with the fourth-order term of their phase acting as an asphere. Gradient-index
+    /// media only contribute through the rays that reach the surfaces. Returns `None` when some
+    /// surface is not supported by paraxial calculations.
+    pub fn seidel(&self) -> Option<Seidel> {
+        let wavelength = self.primary_wavelength();
+        let first_order = self.first_order(wavelength)?;
+        let model = self.paraxial_model(wavelength, Meridian::Y)?;
+
+        let (short, long) = self
+            .wavelengths
+            .iter()
+            .fold((wavelength, wavelength), |(short, long), wavelength| {
+                (short.min(wavelength.value), long.max(wavelength.value))
+            });
+        let short_model = self.paraxial_model(short, Meridian::Y)?;
+        let long_model = self.paraxial_model(long, Meridian::Y)?;
+
+        let marginal = model.trace(first_order.marginal_ray);
+        let chief = model.trace(first_order.chief_ray);
+        let invariant = first_order.lagrange_invariant;
+
+        // Index before the i-th surface of a model
+        let index_before = |model: &ParaxialModel, i: usize| match i {
+            0 => model.object_index,
+            i => model.surfaces[i - 1].refractive_index,
+        };
+
+        let surfaces: Vec<_> = (0..model.surfaces.len())
+            .filter_map(|i| {
+                let index = model.surfaces[i].index;
+                let surface = &self.surfaces[index];
+
+                if matches!(surface.kind(), SurfaceKind::Image | SurfaceKind::Paraxial) {
+                    return None;
+                }
+
+                let (n, n_after) = (index_before(&model, i), model.surfaces[i].refractive_index);
+                let (y, w) = (marginal[i].height, marginal[i].incident);
+                let (y_chief, w_chief) = (chief[i].height, chief[i].incident);
+                let curvature = surface.paraxial_curvature(Meridian::Y)?;
+
+                // Refraction invariants A = n i and Ā = n ī, and the change of u / n through the
+                // substrate alone, which leaves out the power of diffractive phases
+                let a = w + n * y * curvature;
+                let a_chief = w_chief + n * y_chief * curvature;
+                let w_after = w - (n_after - n) * curvature * y;
+                let delta = w_after / (n_after * n_after) - w / (n * n);
+
+                let petzval = -invariant * invariant * curvature * (1.0 / n_after - 1.0 / n);
+                let astigmatism = -a_chief * a_chief * y * delta;
+
+                let mut coefficients = SeidelCoefficients {
+                    spherical: -a * a * y * delta,
+                    coma: -a * a_chief * y * delta,
+                    astigmatism,
+                    petzval,
+                    distortion: if a != 0.0 {
+                        a_chief / a * (astigmatism + petzval)
+                    } else {
+                        0.0
+                    },
+                    axial_color: 0.0,
+                    lateral_color: 0.0,
+                };
+
+                // Fourth-order terms add an optical path W r⁴, which contributes -8 W y⁴ to
+                // spherical aberration, and to the other terms with the chief ray height
+                let mut fourth_order = (n - n_after) * fourth_order_sag(surface);
+
+                if surface.kind() == SurfaceKind::Binary2 {
+                    fourth_order +=
+                        binary2::fourth_order(surface.data(), surface.coefficients(), wavelength);
+                }
+
+                let k = -8.0 * fourth_order;
+                coefficients.spherical += k * y.powi(4);
+                coefficients.coma += k * y.powi(3) * y_chief;
+                coefficients.astigmatism += k * y * y * y_chief * y_chief;
+                coefficients.distortion += k * y * y_chief.powi(3);
+
+                // Dispersion of the media around the surface, δn = n(short) - n(long)
+                let dispersion = |n: f32, short: f32, long: f32| (short - long) / n;
+                let color = dispersion(
+                    n_after,
+                    short_model.surfaces[i].refractive_index,
+                    long_model.surfaces[i].refractive_index,
+                ) - dispersion(
+                    n,
+                    index_before(&short_model, i),
+                    index_before(&long_model, i),
+                );
+
+                coefficients.axial_color = a * y * color;
+                coefficients.lateral_color = a_chief * y * color;
+
+                // Diffractive phases disperse like thin lenses of power δφ, with C_L = y² δφ
+                if surface.kind() == SurfaceKind::Binary2 {
+                    let dispersion = binary2::power(surface.data(), surface.coefficients(), short)
+                        - binary2::power(surface.data(), surface.coefficients(), long);
+
+                    coefficients.axial_color += y * y * dispersion;
+                    coefficients.lateral_color += y * y_chief * dispersion;
+                }
+
+                Some((index, coefficients))
+            })
+            .collect();
+
+        let total = surfaces.iter().map(|(_, coefficients)| *coefficients).sum();
+
+        Some(Seidel { surfaces, total })
+    }
+}
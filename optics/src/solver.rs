@@ -0,0 +1,101 @@
+use crate::glam::{Mat2, Vec2};
+
+/// Maximum number of iterations of [`newton`].
+const MAX_ITERATIONS: usize = 16;
+
+/// Finds the input for which `function` returns `goal`, starting from `initial`.
+///
+/// Derivatives are estimated with forward differences of size `step`, and iterations stop once the
+/// output is closer than `tolerance` to `goal`. When `function` fails or its derivatives vanish,
+/// the last input is returned as is.
+pub(crate) fn newton(
+    initial: Vec2,
+    goal: Vec2,
+    step: f32,
+    tolerance: f32,
+    function: impl Fn(Vec2) -> Option<Vec2>,
+) -> Vec2 {
+    let mut input = initial;
+
+    for _ in 0..MAX_ITERATIONS {
+        let Some(output) = function(input) else {
+            break;
+        };
+
+        let error = output - goal;
+
+        if error.length() < tolerance {
+            break;
+        }
+
+        let (Some(dx), Some(dy)) = (
+            function(input + Vec2::X * step),
+            function(input + Vec2::Y * step),
+        ) else {
+            break;
+        };
+
+        let jacobian = Mat2::from_cols((dx - output) / step, (dy - output) / step);
+
+        if jacobian.determinant().abs() < f32::EPSILON {
+            break;
+        }
+
+        input -= jacobian.inverse() * error;
+    }
+
+    input
+}
+
+/// Solves the square linear system `matrix` x = `rhs` by Gaussian elimination with partial
+/// pivoting, where `matrix` is stored row by row. Returns `None` when the system is singular.
+pub(crate) fn solve_linear(mut matrix: Vec<f64>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let size = rhs.len();
+
+    if matrix.len() != size * size {
+        return None;
+    }
+
+    for column in 0..size {
+        let pivot = (column..size).max_by(|&a, &b| {
+            matrix[a * size + column]
+                .abs()
+                .total_cmp(&matrix[b * size + column].abs())
+        })?;
+
+        if matrix[pivot * size + column].abs() < f64::EPSILON {
+            return None;
+        }
+
+        if pivot != column {
+            for k in 0..size {
+                matrix.swap(pivot * size + k, column * size + k);
+            }
+
+            rhs.swap(pivot, column);
+        }
+
+        for row in column + 1..size {
+            let factor = matrix[row * size + column] / matrix[column * size + column];
+
+            for k in column..size {
+                matrix[row * size + k] -= factor * matrix[column * size + k];
+            }
+
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    // Back substitution, from the last row up
+    let mut solution = vec![0.0; size];
+
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size)
+            .map(|k| matrix[row * size + k] * solution[k])
+            .sum();
+
+        solution[row] = (rhs[row] - sum) / matrix[row * size + row];
+    }
+
+    Some(solution)
+}
@@ -0,0 +1,151 @@
+use rayon::prelude::*;
+
+use crate::{fields::FieldPoint, glam::Vec2, pupil::pupil_grid, ray::Wavelength, system::System};
+
+/// Point spot diagrams are measured from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpotReference {
+    /// Point where the chief ray of the primary wavelength reaches the image.
+    #[default]
+    ChiefRay,
+    /// Weighted centroid of the rays of every wavelength.
+    Centroid,
+}
+
+impl SpotReference {
+    pub const ALL: [Self; 2] = [Self::ChiefRay, Self::Centroid];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ChiefRay => "Chief ray",
+            Self::Centroid => "Centroid",
+        }
+    }
+}
+
+/// Rays of a single wavelength on the image surface.
+#[derive(Debug, Clone)]
+pub struct Spot {
+    pub wavelength: Wavelength,
+    /// Weight of the wavelength, normalized over the spectrum of the system.
+    pub weight: f32,
+    /// Points where rays reached the image, relative to the reference point.
+    pub points: Vec<Vec2>,
+}
+
+/// Spot diagram of a field point.
+#[derive(Debug, Clone)]
+pub struct SpotDiagram {
+    /// Point of the image surface spots are measured from, in its local coordinates.
+    pub reference: Vec2,
+    pub spots: Vec<Spot>,
+    /// Weighted RMS distance from rays to the reference point.
+    pub rms_radius: f32,
+    /// Largest distance from a ray to the reference point.
+    pub geometric_radius: f32,
+}
+
+// Implementation of spot diagram methods
+impl System {
+    /// Traces a grid of `sampling` by `sampling` rays over the pupil of `point` for every
+    /// wavelength, see [`pupil_grid`]. Vignetted rays are left out. Returns `None` when the field
+    /// point cannot be defined or the reference point cannot be found.
+    pub fn spot_diagram(
+        &self,
+        point: &FieldPoint,
+        sampling: usize,
+        reference: SpotReference,
+    ) -> Option<SpotDiagram> {
+        let pupil: Vec<_> = pupil_grid(sampling).collect();
+
+        let mut spots: Vec<_> = self
+            .weighted_wavelengths()
+            .map(|(wavelength, weight)| {
+                let field = self.object_field(point, wavelength);
+                let points = pupil
+                    .par_iter()
+                    .filter_map(|pupil| {
+                        let ray = self.aim(field?, point.vignetting.apply(*pupil), wavelength);
+                        let trace = self.trace(ray);
+
+                        if !trace.is_complete() {
+                            return None;
+                        }
+
+                        Some(trace.last()?.local_point.truncate())
+                    })
+                    .collect();
+
+                Spot {
+                    wavelength,
+                    weight,
+                    points,
+                }
+            })
+            .collect();
+
+        let reference = match reference {
+            SpotReference::ChiefRay => self.chief_ray_image(point, self.primary_wavelength())?,
+            SpotReference::Centroid => {
+                let (sum, total) = spots.iter().filter(|spot| !spot.points.is_empty()).fold(
+                    (Vec2::ZERO, 0.0),
+                    |(sum, total), spot| {
+                        let centroid = spot.points.iter().sum::<Vec2>() / spot.points.len() as f32;
+
+                        (sum + spot.weight * centroid, total + spot.weight)
+                    },
+                );
+
+                if total <= 0.0 {
+                    return None;
+                }
+
+                sum / total
+            }
+        };
+
+        let mut squared_sum = 0.0;
+        let mut total = 0.0;
+        let mut geometric_radius: f32 = 0.0;
+
+        for spot in &mut spots {
+            for point in &mut spot.points {
+                *point -= reference;
+                geometric_radius = geometric_radius.max(point.length());
+            }
+
+            // Every wavelength contributes to the RMS radius with its own weight, whatever the
+            // number of rays that made it through
+            if !spot.points.is_empty() {
+                let squared = spot
+                    .points
+                    .iter()
+                    .map(|point| point.length_squared())
+                    .sum::<f32>();
+
+                squared_sum += spot.weight * squared / spot.points.len() as f32;
+                total += spot.weight;
+            }
+        }
+
+        Some(SpotDiagram {
+            reference,
+            spots,
+            rms_radius: if total > 0.0 {
+                (squared_sum / total).sqrt()
+            } else {
+                0.0
+            },
+            geometric_radius,
+        })
+    }
+
+    /// Radius of the first dark ring of the Airy pattern at `wavelength`, 1.22 λ N, where N is the
+    /// paraxial working F-number of the system.
+    pub fn airy_radius(&self, wavelength: Wavelength) -> Option<f32> {
+        let first_order = self.first_order(wavelength)?;
+
+        // Wavelengths are in micrometers and lengths in millimeters
+        Some(1.22 * wavelength * 1e-3 * first_order.working_f_number)
+    }
+}
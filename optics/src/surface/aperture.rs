@@ -0,0 +1,121 @@
+use crate::glam::Vec2;
+
+/// Outline of an aperture, centered on the origin of its coordinate system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApertureShape {
+    Circular {
+        radius: f32,
+    },
+    Annular {
+        inner_radius: f32,
+        outer_radius: f32,
+    },
+    Rectangular {
+        half_width: f32,
+        half_height: f32,
+    },
+    Elliptical {
+        semi_axis_x: f32,
+        semi_axis_y: f32,
+    },
+    /// Vanes of width `width` that go from the center outwards, evenly spaced starting from +X.
+    Spider {
+        arms: u32,
+        width: f32,
+    },
+}
+
+impl ApertureShape {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Circular { .. } => "Circular",
+            Self::Annular { .. } => "Annular",
+            Self::Rectangular { .. } => "Rectangular",
+            Self::Elliptical { .. } => "Elliptical",
+            Self::Spider { .. } => "Spider",
+        }
+    }
+
+    /// Whether `point` lies inside the outline.
+    pub fn contains(&self, point: Vec2) -> bool {
+        match *self {
+            Self::Circular { radius } => point.length_squared() <= radius * radius,
+            Self::Annular {
+                inner_radius,
+                outer_radius,
+            } => {
+                let r2 = point.length_squared();
+                inner_radius * inner_radius <= r2 && r2 <= outer_radius * outer_radius
+            }
+            Self::Rectangular {
+                half_width,
+                half_height,
+            } => point.x.abs() <= half_width && point.y.abs() <= half_height,
+            Self::Elliptical {
+                semi_axis_x,
+                semi_axis_y,
+            } => (point / Vec2::new(semi_axis_x, semi_axis_y)).length_squared() <= 1.0,
+            Self::Spider { arms, width } => (0..arms).any(|arm| {
+                let angle = core::f32::consts::TAU * arm as f32 / arms as f32;
+                let direction = Vec2::from_angle(angle);
+
+                direction.dot(point) >= 0.0 && direction.perp_dot(point).abs() <= 0.5 * width
+            }),
+        }
+    }
+
+    /// Distance from the center to the farthest point of the outline.
+    pub fn extent(&self) -> f32 {
+        match *self {
+            Self::Circular { radius } => radius,
+            Self::Annular { outer_radius, .. } => outer_radius,
+            Self::Rectangular {
+                half_width,
+                half_height,
+            } => half_width.hypot(half_height),
+            Self::Elliptical {
+                semi_axis_x,
+                semi_axis_y,
+            } => semi_axis_x.max(semi_axis_y),
+            // Vanes reach as far as the apertures they are combined with
+            Self::Spider { .. } => 0.0,
+        }
+    }
+}
+
+/// Clear aperture or obscuration on a surface, given in the local coordinates of the surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aperture {
+    pub shape: ApertureShape,
+    /// Obscurations block the rays inside the outline instead of the ones outside of it.
+    pub obscuration: bool,
+    /// Position of the center of the outline.
+    pub decenter: Vec2,
+    /// Rotation of the outline around its center, in degrees.
+    pub rotation: f32,
+}
+
+impl Aperture {
+    pub const fn new(shape: ApertureShape) -> Self {
+        Self {
+            shape,
+            obscuration: false,
+            decenter: Vec2::ZERO,
+            rotation: 0.0,
+        }
+    }
+
+    pub const fn obscuration(shape: ApertureShape) -> Self {
+        Self {
+            obscuration: true,
+            ..Self::new(shape)
+        }
+    }
+
+    /// Whether a ray that hits the surface at the local point (x, y) goes through the aperture.
+    pub fn passes(&self, point: Vec2) -> bool {
+        let point = Vec2::from_angle(-self.rotation.to_radians()).rotate(point - self.decenter);
+
+        self.shape.contains(point) != self.obscuration
+    }
+}
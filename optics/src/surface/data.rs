@@ -1,6 +1,6 @@
 use crate::surface::field::Field;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SurfaceData([Field; Self::LEN]);
 
 impl core::ops::Deref for SurfaceData {
@@ -24,3 +24,45 @@ pub const TRANSLATION_Z: usize = assert_field!(7);
 pub const ROTATION_X: usize = assert_field!(8);
 pub const ROTATION_Y: usize = assert_field!(9);
 pub const ROTATION_Z: usize = assert_field!(10);
+
+pub const CONIC: usize = assert_field!(11);
+
+pub const ASPHERE_R2: usize = assert_field!(12);
+pub const ASPHERE_R4: usize = assert_field!(13);
+pub const ASPHERE_R6: usize = assert_field!(14);
+pub const ASPHERE_R8: usize = assert_field!(15);
+pub const ASPHERE_R10: usize = assert_field!(16);
+pub const ASPHERE_R12: usize = assert_field!(17);
+pub const ASPHERE_R14: usize = assert_field!(18);
+pub const ASPHERE_R16: usize = assert_field!(19);
+
+/// Even asphere coefficients, ordered by the power of r they multiply.
+pub const ASPHERE_TERMS: [usize; 8] = [
+    ASPHERE_R2,
+    ASPHERE_R4,
+    ASPHERE_R6,
+    ASPHERE_R8,
+    ASPHERE_R10,
+    ASPHERE_R12,
+    ASPHERE_R14,
+    ASPHERE_R16,
+];
+
+pub const FOCAL_LENGTH: usize = assert_field!(20);
+pub const OPD_MODE: usize = assert_field!(21);
+
+/// Non-zero when the surface reflects light instead of refracting it.
+pub const MIRROR: usize = assert_field!(22);
+
+pub const CURVATURE_X: usize = assert_field!(23);
+
+/// Radius that maps to the edge of the unit circle in polynomial surfaces.
+pub const NORM_RADIUS: usize = assert_field!(24);
+pub const ZERNIKE_ORDERING: usize = assert_field!(25);
+pub const CONIC_X: usize = assert_field!(26);
+
+/// Grating lines per micrometer, the same unit as wavelengths.
+pub const LINE_DENSITY: usize = assert_field!(27);
+pub const DIFFRACTION_ORDER: usize = assert_field!(28);
+pub const GRATING_ANGLE: usize = assert_field!(29);
+pub const DESIGN_WAVELENGTH: usize = assert_field!(30);
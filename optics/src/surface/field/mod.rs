@@ -3,7 +3,7 @@ mod kind;
 
 pub use index::*;
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 #[repr(transparent)]
 pub struct Field(pub(crate) u32);
 
@@ -0,0 +1,72 @@
+#[cfg(target_arch = "spirv")]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    glam::Vec2,
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{
+        CONIC, CONIC_X, CURVATURE, CURVATURE_X, Placement, SurfaceData,
+        kind::{intersect_sag, local_ray, spherical},
+    },
+};
+
+// A biconic has independent curvatures and conic constants on the XZ and YZ planes, with sag
+// z = (cx x² + cy y²) / (1 + √(1 - (1 + kx) cx² x² - (1 + ky) cy² y²)). `CURVATURE` and `CONIC`
+// describe the YZ profile, as in rotationally symmetric surfaces.
+
+/// Sag of the surface at the local point (x, y), or `None` outside of the surface.
+pub fn sag(data: &SurfaceData, x: f32, y: f32) -> Option<f32> {
+    sag_and_gradient(data, Vec2::new(x, y)).map(|(sag, _)| sag)
+}
+
+fn sag_and_gradient(data: &SurfaceData, point: Vec2) -> Option<(f32, Vec2)> {
+    let curvature = Vec2::new(data[CURVATURE_X].into(), data[CURVATURE].into());
+    let conic = Vec2::new(data[CONIC_X].into(), data[CONIC].into());
+
+    // Each component holds the contribution of one meridian
+    let numerator = curvature * point * point;
+    let weights = (Vec2::ONE + conic) * curvature * curvature;
+
+    let root = 1.0 - weights.dot(point * point);
+
+    if root < 0.0 {
+        return None;
+    }
+
+    let root = root.sqrt();
+    let denominator = 1.0 + root;
+
+    let sag = numerator.element_sum() / denominator;
+
+    // d(root)/dx = -(1 + kx) cx² x / root, and the same for y
+    let root_gradient = -weights * point / root;
+    let gradient = (2.0 * curvature * point - sag * root_gradient) / denominator;
+
+    Some((sag, gradient))
+}
+
+pub fn intersect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+
+    let start = spherical::intersect_conic(
+        origin,
+        direction,
+        data[CURVATURE].into(),
+        data[CONIC].into(),
+    )
+    .unwrap_or(-origin.z / direction.z);
+
+    let (t, normal) = intersect_sag(origin, direction, start, |point| {
+        sag_and_gradient(data, point)
+    })?;
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
@@ -0,0 +1,146 @@
+use crate::{
+    glam::{Vec2, Vec3Swizzles},
+    intersection::Intersection,
+    ray::Wavelength,
+    refracted_ray::{RayFailure, RefractedRay, RefractiveIndex},
+    surface::{
+        DESIGN_WAVELENGTH, DIFFRACTION_ORDER, Deflection, NORM_RADIUS, Placement, SurfaceData,
+        kind::local_ray,
+    },
+};
+
+// Binary 2 phase surfaces add a rotationally symmetric phase, Φ = m Σ A_i ρ^(2i) with
+// ρ = r / `NORM_RADIUS` and m the diffraction order, on top of a conic substrate.
+// `coefficients[i - 1]` holds A_i in radians. At a wavelength λ, the surface adds an optical path
+// of Φ λ / 2π. Terms are ignored until the normalization radius is set to a positive value.
+//
+// The zones of the surface are blazed so that their profile delays the design wavelength by m
+// waves. Rays follow the diffraction order at every wavelength, but only the design wavelength
+// goes into it entirely, see `efficiency`.
+
+/// Factor that turns the coefficients into optical paths at `wavelength`, or `None` when the phase
+/// is not defined.
+fn scale(data: &SurfaceData, wavelength: Wavelength) -> Option<f32> {
+    let order: i32 = data[DIFFRACTION_ORDER].into();
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+
+    // Wavelengths are in micrometers and lengths in millimeters
+    (norm_radius > 0.0).then(|| order as f32 * wavelength * 1e-3 / std::f32::consts::TAU)
+}
+
+/// Fraction of the light at `wavelength` that goes into the diffraction order, or `None` when the
+/// design wavelength is not set. Uses the scalar theory of thin blazed zones and neglects the
+/// dispersion of the substrate, so the profile delays λ by m λ₀ / λ waves.
+pub fn efficiency(data: &SurfaceData, wavelength: Wavelength) -> Option<f32> {
+    let order: i32 = data[DIFFRACTION_ORDER].into();
+    let design_wavelength: f32 = data[DESIGN_WAVELENGTH].into();
+
+    (design_wavelength > 0.0).then(|| {
+        let detuning = std::f32::consts::PI * order as f32 * (design_wavelength / wavelength - 1.0);
+
+        if detuning == 0.0 {
+            1.0
+        } else {
+            (detuning.sin() / detuning).powi(2)
+        }
+    })
+}
+
+/// Optical path added by the surface at the local point, and its gradient.
+fn phase_and_gradient(
+    data: &SurfaceData,
+    coefficients: &[f32],
+    wavelength: Wavelength,
+    point: Vec2,
+) -> (f32, Vec2) {
+    let Some(scale) = scale(data, wavelength) else {
+        return (0.0, Vec2::ZERO);
+    };
+
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let rho2 = point.length_squared() / (norm_radius * norm_radius);
+
+    let mut phase = 0.0;
+    let mut slope = 0.0;
+
+    // rho2_power holds ρ^(2i - 2) for the term that multiplies ρ^(2i)
+    let mut rho2_power = 1.0;
+
+    for (i, &coefficient) in (1..).zip(coefficients) {
+        slope += 2.0 * i as f32 * coefficient * rho2_power;
+        rho2_power *= rho2;
+        phase += coefficient * rho2_power;
+    }
+
+    (
+        scale * phase,
+        scale * slope * point / (norm_radius * norm_radius),
+    )
+}
+
+/// Paraxial power of the phase at `wavelength`, which does not include the substrate.
+pub fn power(data: &SurfaceData, coefficients: &[f32], wavelength: Wavelength) -> f32 {
+    let Some(scale) = scale(data, wavelength) else {
+        return 0.0;
+    };
+
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let first = coefficients.first().copied().unwrap_or_default();
+
+    // An optical path of -P r² / 2 bends rays like a thin lens of power P
+    -2.0 * scale * first / (norm_radius * norm_radius)
+}
+
+/// Coefficient of r⁴ in the optical path added by the phase at `wavelength`.
+pub fn fourth_order(data: &SurfaceData, coefficients: &[f32], wavelength: Wavelength) -> f32 {
+    let Some(scale) = scale(data, wavelength) else {
+        return 0.0;
+    };
+
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let second = coefficients.get(1).copied().unwrap_or_default();
+
+    scale * second / norm_radius.powi(4)
+}
+
+/// Deflects `ray` at `intersection` by the gradient of the phase.
+pub fn deflect(
+    data: &SurfaceData,
+    coefficients: &[f32],
+    refracted_ray: &RefractedRay,
+    intersection: &Intersection,
+    placement: &Placement,
+    refractive_index: RefractiveIndex,
+) -> Result<Deflection, RayFailure> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+    let point = (origin + direction * intersection.t).xy();
+
+    let (optical_path, gradient) =
+        phase_and_gradient(data, coefficients, refracted_ray.wavelength, point);
+
+    let phase_gradient = placement.transform.transform_vector3(gradient.extend(0.0));
+
+    Ok(Deflection {
+        ray: refracted_ray.diffract(intersection, refractive_index, phase_gradient, false)?,
+        optical_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn efficiency_peaks_at_design_wavelength() {
+        let data = SurfaceData::default()
+            .with(DIFFRACTION_ORDER, 1)
+            .with(DESIGN_WAVELENGTH, 0.55);
+
+        assert_eq!(efficiency(&SurfaceData::default(), 0.55), None);
+        assert_eq!(efficiency(&data, 0.55), Some(1.0));
+
+        // Half a wave of detuning in the first order
+        let efficiency = efficiency(&data, 0.55 / 1.5).unwrap();
+        assert!((efficiency - 4.0 / std::f32::consts::PI.powi(2)).abs() < 1e-5);
+    }
+}
@@ -1,8 +1,22 @@
 use crate::{
-    glam::{Mat4, vec3},
+    glam::{Mat4, Vec3, vec3},
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
     surface::*,
 };
 
+/// Coordinate breaks do not interact with rays, which pass through them unchanged.
+pub fn intersect(
+    _data: &SurfaceData,
+    _refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(Vec3::NEG_Z),
+        t: 0.0,
+    })
+}
+
 pub fn transformation_matrix(data: &SurfaceData) -> Mat4 {
     let translation = vec3(
         data[TRANSLATION_X].into(),
@@ -0,0 +1,94 @@
+#[cfg(target_arch = "spirv")]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    glam::Vec2,
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{
+        ASPHERE_R2, ASPHERE_R4, ASPHERE_TERMS, CONIC, CURVATURE, Placement, SurfaceData,
+        kind::{intersect_sag, local_ray, spherical},
+    },
+};
+
+/// Sag of the surface at a distance `r` from the optical axis.
+pub fn sag(data: &SurfaceData, r: f32) -> f32 {
+    let r2 = r * r;
+
+    spherical::conic_sag(data[CURVATURE].into(), data[CONIC].into(), r2)
+        + ASPHERE_TERMS
+            .iter()
+            .rev()
+            .fold(0.0, |sum, &term| (sum + f32::from(data[term])) * r2)
+}
+
+/// Curvature of the sphere that matches the surface near its vertex.
+pub fn paraxial_curvature(data: &SurfaceData) -> f32 {
+    let curvature: f32 = data[CURVATURE].into();
+    let r2: f32 = data[ASPHERE_R2].into();
+
+    curvature + 2.0 * r2
+}
+
+/// Coefficient of r⁴ in the difference between the sag and the sphere of `paraxial_curvature`.
+pub fn fourth_order(data: &SurfaceData) -> f32 {
+    let curvature: f32 = data[CURVATURE].into();
+    let conic: f32 = data[CONIC].into();
+    let r4: f32 = data[ASPHERE_R4].into();
+    let paraxial = paraxial_curvature(data);
+
+    ((1.0 + conic) * curvature.powi(3) - paraxial.powi(3)) / 8.0 + r4
+}
+
+/// Sag and its gradient at `point`, or `None` outside of the base conic.
+fn sag_and_gradient(data: &SurfaceData, point: Vec2) -> Option<(f32, Vec2)> {
+    let curvature: f32 = data[CURVATURE].into();
+    let conic: f32 = data[CONIC].into();
+    let r2 = point.length_squared();
+
+    if (1.0 + conic) * curvature * curvature * r2 > 1.0 {
+        return None;
+    }
+
+    let mut sag = spherical::conic_sag(curvature, conic, r2);
+    let mut slope = spherical::conic_slope(curvature, conic, r2);
+
+    // r2_power holds r^(2i - 2) for the term that multiplies r^(2i)
+    let mut r2_power = 1.0;
+
+    for (i, &term) in ASPHERE_TERMS.iter().enumerate() {
+        let coefficient: f32 = data[term].into();
+
+        slope += 2.0 * (i + 1) as f32 * coefficient * r2_power;
+        r2_power *= r2;
+        sag += coefficient * r2_power;
+    }
+
+    Some((sag, point * slope))
+}
+
+pub fn intersect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+
+    // The base conic is a good starting point unless the aspheric terms are very strong
+    let start = spherical::intersect_conic(
+        origin,
+        direction,
+        data[CURVATURE].into(),
+        data[CONIC].into(),
+    )
+    .unwrap_or(-origin.z / direction.z);
+
+    let (t, normal) = intersect_sag(origin, direction, start, |point| {
+        sag_and_gradient(data, point)
+    })?;
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
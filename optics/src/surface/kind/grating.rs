@@ -0,0 +1,53 @@
+use crate::{
+    glam::{Vec2, Vec3Swizzles},
+    intersection::Intersection,
+    refracted_ray::{RayFailure, RefractedRay, RefractiveIndex},
+    surface::{
+        DIFFRACTION_ORDER, Deflection, GRATING_ANGLE, LINE_DENSITY, Placement, SurfaceData,
+        kind::local_ray,
+    },
+};
+
+// Gratings are ruled on a conic substrate, sharing the geometry of spherical surfaces. The grating
+// vector lies on the local XY plane and points along Y when `GRATING_ANGLE` is zero, so the lines
+// are parallel to X and light is dispersed on the YZ plane. Positive angles rotate it towards -X.
+
+/// Grating vector on the local XY plane, with a length of `LINE_DENSITY`.
+fn grating_vector(data: &SurfaceData) -> Vec2 {
+    let angle: f32 = data[GRATING_ANGLE].into();
+    let line_density: f32 = data[LINE_DENSITY].into();
+
+    Vec2::from_angle(angle.to_radians()).rotate(Vec2::Y) * line_density
+}
+
+/// Diffracts `ray` at `intersection` into the selected order, using the grating equation
+/// n' t' = n t + m λ g. On curved substrates the grating vector g is projected onto the tangent
+/// plane. Orders that cannot propagate fail with [`RayFailure::EvanescentOrder`].
+pub fn deflect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    intersection: &Intersection,
+    placement: &Placement,
+    refractive_index: RefractiveIndex,
+    reflective: bool,
+) -> Result<Deflection, RayFailure> {
+    let order: i32 = data[DIFFRACTION_ORDER].into();
+    let order = order as f32;
+    let wavelength = refracted_ray.wavelength;
+    let grating = grating_vector(data);
+
+    let (origin, direction) = local_ray(refracted_ray, placement);
+    let point = (origin + direction * intersection.t).xy();
+
+    let phase_gradient =
+        placement.transform.transform_vector3(grating.extend(0.0)) * order * wavelength;
+
+    // Phase of the grating, m λ times the number of lines crossed. Wavelengths are in micrometers
+    // and lengths in millimeters.
+    let optical_path = order * wavelength * grating.dot(point);
+
+    Ok(Deflection {
+        ray: refracted_ray.diffract(intersection, refractive_index, phase_gradient, reflective)?,
+        optical_path,
+    })
+}
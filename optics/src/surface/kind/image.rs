@@ -1 +1,13 @@
+use crate::{
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{Placement, SurfaceData, kind::intersect_plane},
+};
 
+pub fn intersect(
+    _data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    intersect_plane(refracted_ray, placement)
+}
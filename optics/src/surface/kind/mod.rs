@@ -1,9 +1,22 @@
-use crate::surface::*;
+use crate::{
+    glam::{Vec2, Vec3, Vec3Swizzles},
+    intersection::Intersection,
+    ray::Ray,
+    surface::*,
+};
 
+pub mod biconic;
+pub mod binary2;
 pub mod coordinate_break;
+pub mod even_asphere;
+pub mod grating;
 pub mod image;
 pub mod object;
+pub mod paraxial;
 pub mod spherical;
+pub mod toroidal;
+pub mod xy_polynomial;
+pub mod zernike_sag;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[non_exhaustive]
@@ -13,19 +26,55 @@ pub enum SurfaceKind {
     Image,
     Spherical,
     CoordinateBreak,
+    EvenAsphere,
+    Paraxial,
+    Toroidal,
+    ZernikeSag,
+    XyPolynomial,
+    Biconic,
+    Grating,
+    Binary2,
 }
 
 #[cfg(not(target_arch = "spirv"))]
 impl SurfaceKind {
+    pub const ALL: &'static [Self] = &[
+        Self::Object,
+        Self::Image,
+        Self::Spherical,
+        Self::CoordinateBreak,
+        Self::EvenAsphere,
+        Self::Paraxial,
+        Self::Toroidal,
+        Self::ZernikeSag,
+        Self::XyPolynomial,
+        Self::Biconic,
+        Self::Grating,
+        Self::Binary2,
+    ];
+
     pub const fn name(&self) -> &'static str {
         match self {
             Self::Object => "Object",
             Self::Image => "Image",
             Self::Spherical => "Spherical",
             Self::CoordinateBreak => "CoordinateBreak",
+            Self::EvenAsphere => "EvenAsphere",
+            Self::Paraxial => "Paraxial",
+            Self::Toroidal => "Toroidal",
+            Self::ZernikeSag => "ZernikeSag",
+            Self::XyPolynomial => "XyPolynomial",
+            Self::Biconic => "Biconic",
+            Self::Grating => "Grating",
+            Self::Binary2 => "Binary2",
         }
     }
 
+    /// Whether surfaces of this kind use the coefficient block besides their fields.
+    pub const fn has_coefficients(&self) -> bool {
+        matches!(self, Self::ZernikeSag | Self::XyPolynomial | Self::Binary2)
+    }
+
     pub const fn fields(&self) -> &'static [Option<&'static str>; SurfaceData::LEN] {
         match self {
             Self::Object => {
@@ -51,6 +100,8 @@ impl SurfaceKind {
                     fields[THICKNESS] = Some("thickness");
                     fields[MATERIAL_INDEX] = Some("material_index");
                     fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
                     fields
                 }
             }
@@ -67,6 +118,175 @@ impl SurfaceKind {
                     fields
                 }
             }
+            Self::EvenAsphere => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[ASPHERE_R2] = Some("asphere_r2");
+                    fields[ASPHERE_R4] = Some("asphere_r4");
+                    fields[ASPHERE_R6] = Some("asphere_r6");
+                    fields[ASPHERE_R8] = Some("asphere_r8");
+                    fields[ASPHERE_R10] = Some("asphere_r10");
+                    fields[ASPHERE_R12] = Some("asphere_r12");
+                    fields[ASPHERE_R14] = Some("asphere_r14");
+                    fields[ASPHERE_R16] = Some("asphere_r16");
+                    fields[MIRROR] = Some("mirror");
+                    fields
+                }
+            }
+            Self::Paraxial => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[THICKNESS] = Some("thickness");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[FOCAL_LENGTH] = Some("focal_length");
+                    fields[OPD_MODE] = Some("opd_mode");
+                    fields
+                }
+            }
+            Self::Toroidal => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
+                    fields[CURVATURE_X] = Some("curvature_x");
+                    fields[CONIC_X] = Some("conic_x");
+                    fields
+                }
+            }
+            Self::ZernikeSag => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
+                    fields[NORM_RADIUS] = Some("norm_radius");
+                    fields[ZERNIKE_ORDERING] = Some("zernike_ordering");
+                    fields
+                }
+            }
+            Self::XyPolynomial => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
+                    fields[NORM_RADIUS] = Some("norm_radius");
+                    fields
+                }
+            }
+            Self::Biconic => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
+                    fields[CURVATURE_X] = Some("curvature_x");
+                    fields[CONIC_X] = Some("conic_x");
+                    fields
+                }
+            }
+            Self::Grating => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[MIRROR] = Some("mirror");
+                    fields[LINE_DENSITY] = Some("line_density");
+                    fields[DIFFRACTION_ORDER] = Some("diffraction_order");
+                    fields[GRATING_ANGLE] = Some("grating_angle");
+                    fields
+                }
+            }
+            Self::Binary2 => {
+                &const {
+                    let mut fields = [None; SurfaceData::LEN];
+                    fields[CURVATURE] = Some("curvature");
+                    fields[THICKNESS] = Some("thickness");
+                    fields[MATERIAL_INDEX] = Some("material_index");
+                    fields[SEMI_DIAMETER] = Some("semi_diameter");
+                    fields[CONIC] = Some("conic");
+                    fields[NORM_RADIUS] = Some("norm_radius");
+                    fields[DIFFRACTION_ORDER] = Some("diffraction_order");
+                    fields[DESIGN_WAVELENGTH] = Some("design_wavelength");
+                    fields
+                }
+            }
+        }
+    }
+}
+
+/// Origin and direction of `ray` in the local coordinate system of a surface placed at `placement`.
+pub(crate) fn local_ray(ray: &Ray, placement: &Placement) -> (Vec3, Vec3) {
+    (
+        placement.inverse.transform_point3(ray.origin),
+        placement.inverse.transform_vector3(ray.direction),
+    )
+}
+
+/// Intersection of `ray` with the XY plane of the surface placed at `placement`.
+pub(crate) fn intersect_plane(ray: &Ray, placement: &Placement) -> Option<Intersection> {
+    let (origin, direction) = local_ray(ray, placement);
+    let t = -origin.z / direction.z;
+
+    t.is_finite().then(|| Intersection {
+        normal: placement.transform.transform_vector3(Vec3::NEG_Z),
+        t,
+    })
+}
+
+const MAX_ITERATIONS: usize = 32;
+const TOLERANCE: f32 = 1e-6;
+
+/// Intersects a ray with the surface z = sag(x, y) using Newton's method, starting from `t`.
+///
+/// The ray is given in local coordinates and `sag` returns both the sag and its gradient at a
+/// point, or `None` when the point lies outside the surface. Returns the distance along the ray
+/// and the local surface normal, which is not normalized.
+pub(crate) fn intersect_sag(
+    origin: Vec3,
+    direction: Vec3,
+    mut t: f32,
+    sag: impl Fn(Vec2) -> Option<(f32, Vec2)>,
+) -> Option<(f32, Vec3)> {
+    for _ in 0..MAX_ITERATIONS {
+        let point = origin + direction * t;
+        let (z, gradient) = sag(point.xy())?;
+
+        let dt = (point.z - z) / (direction.z - gradient.dot(direction.xy()));
+        t -= dt;
+
+        if !t.is_finite() {
+            return None;
+        }
+
+        if dt.abs() <= TOLERANCE * t.abs().max(1.0) {
+            let point = origin + direction * t;
+            let (_, gradient) = sag(point.xy())?;
+
+            return Some((t, gradient.extend(-1.0)));
         }
     }
+
+    None
 }
@@ -1 +1,54 @@
+use crate::{
+    glam::{Mat4, Vec2, Vec3, vec3},
+    intersection::Intersection,
+    ray::{Ray, Wavelength},
+    refracted_ray::RefractedRay,
+    surface::{Placement, SurfaceData, THICKNESS, kind::intersect_plane},
+};
 
+/// The object plane sits `THICKNESS` before the surface that follows it. Objects at infinity have
+/// no plane to intersect with.
+pub fn intersect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let distance: f32 = data[THICKNESS].into();
+
+    if !distance.is_finite() {
+        return None;
+    }
+
+    intersect_plane(
+        refracted_ray,
+        &Placement {
+            transform: placement.transform * Mat4::from_translation(-distance * Vec3::Z),
+            inverse: Mat4::from_translation(distance * Vec3::Z) * placement.inverse,
+        },
+    )
+}
+
+/// Ray leaving the object towards `target`, in global coordinates.
+///
+/// For objects at a finite distance, `field` is the point of the object plane the ray leaves from.
+/// Objects at infinity emit collimated light, so `field` is instead the angle, in degrees, that
+/// the ray makes with the optical axis on the XZ and YZ planes. Such rays start on the plane
+/// perpendicular to them that contains the global origin, so that rays of the same field start
+/// in phase.
+pub fn ray(data: &SurfaceData, field: Vec2, target: Vec3, wavelength: Wavelength) -> Ray {
+    let distance: f32 = data[THICKNESS].into();
+
+    if distance.is_finite() {
+        let origin = field.extend(-distance);
+        Ray::new(origin, (target - origin).normalize(), wavelength)
+    } else {
+        let slope = vec3(field.x.to_radians().tan(), field.y.to_radians().tan(), 1.0);
+        let direction = slope.normalize();
+
+        Ray::new(
+            target - direction * direction.dot(target),
+            direction,
+            wavelength,
+        )
+    }
+}
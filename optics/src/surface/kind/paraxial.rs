@@ -0,0 +1,87 @@
+use crate::{
+    glam::Vec3Swizzles,
+    intersection::Intersection,
+    ray::Ray,
+    refracted_ray::{RayFailure, RefractedRay},
+    surface::{
+        Deflection, FOCAL_LENGTH, OPD_MODE, Placement, SurfaceData,
+        kind::{intersect_plane, local_ray},
+    },
+};
+
+/// How the optical path through a paraxial surface is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OpdMode {
+    /// Optical path of a perfect lens, exact for objects at infinity.
+    Ideal = 0,
+    /// Quadratic phase of a thin lens, -r² / 2f.
+    Quadratic = 1,
+}
+
+impl OpdMode {
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Quadratic,
+            _ => Self::Ideal,
+        }
+    }
+}
+
+pub fn intersect(
+    _data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    intersect_plane(refracted_ray, placement)
+}
+
+pub fn power(data: &SurfaceData) -> f32 {
+    let focal_length: f32 = data[FOCAL_LENGTH].into();
+    focal_length.recip()
+}
+
+/// Paraxial surfaces are ideal thin lenses, so they bend rays by changing their slope by -h / f,
+/// where h is the height the ray hits the surface.
+pub fn deflect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    intersection: &Intersection,
+    placement: &Placement,
+) -> Result<Deflection, RayFailure> {
+    let focal_length: f32 = data[FOCAL_LENGTH].into();
+    let n = refracted_ray.refractive_index;
+
+    let (origin, direction) = local_ray(refracted_ray, placement);
+    let height = (origin + direction * intersection.t).xy();
+
+    let slope = direction.xy() / direction.z;
+    let bent_slope = slope - height / (n * focal_length);
+
+    let bent_direction = bent_slope.extend(1.0).normalize() * direction.z.signum();
+
+    let optical_path = match OpdMode::from_u32(data[OPD_MODE].into()) {
+        OpdMode::Ideal => {
+            n * (focal_length / direction.z.abs()
+                - focal_length / bent_direction.z.abs()
+                - height.dot(direction.xy()))
+        }
+        OpdMode::Quadratic => -n * height.length_squared() / (2.0 * focal_length),
+    };
+
+    if !bent_direction.is_finite() || !optical_path.is_finite() {
+        return Err(RayFailure::Miss);
+    }
+
+    Ok(Deflection {
+        ray: RefractedRay::new(
+            Ray::new(
+                refracted_ray.at(intersection.t),
+                placement.transform.transform_vector3(bent_direction),
+                refracted_ray.wavelength,
+            ),
+            n,
+        ),
+        optical_path,
+    })
+}
@@ -5,7 +5,7 @@ use crate::{
     glam::{Mat4, Vec3},
     intersection::Intersection,
     refracted_ray::RefractedRay,
-    surface::{CURVATURE, SurfaceData, THICKNESS},
+    surface::{CONIC, CURVATURE, Placement, SurfaceData, THICKNESS, kind::local_ray},
 };
 
 pub fn transformation_matrix(data: &SurfaceData) -> Mat4 {
@@ -13,40 +13,152 @@ pub fn transformation_matrix(data: &SurfaceData) -> Mat4 {
     Mat4::from_translation(thickness * Vec3::Z)
 }
 
+/// Sag of the surface at a distance `r` from the optical axis.
+pub fn sag(data: &SurfaceData, r: f32) -> f32 {
+    conic_sag(data[CURVATURE].into(), data[CONIC].into(), r * r)
+}
+
+/// Sag of a conic with curvature `c` and conic constant `k`, at a squared distance `r2` from the
+/// optical axis.
+pub(crate) fn conic_sag(c: f32, k: f32, r2: f32) -> f32 {
+    c * r2 / (1.0 + (1.0 - (1.0 + k) * c * c * r2).sqrt())
+}
+
+/// Derivative of [`conic_sag`] with respect to r, divided by r.
+pub(crate) fn conic_slope(c: f32, k: f32, r2: f32) -> f32 {
+    c / (1.0 - (1.0 + k) * c * c * r2).sqrt()
+}
+
+/// Distance along a ray, in local coordinates, to the conic with curvature `c` and conic constant
+/// `k`. When the ray crosses the conic twice ahead of its origin, the intersection closest to the
+/// vertex plane is returned. Intersections behind the origin are rejected.
+pub(crate) fn intersect_conic(origin: Vec3, direction: Vec3, c: f32, k: f32) -> Option<f32> {
+    // The surface is c (x² + y² + (1 + k) z²) - 2z = 0, so the ray intersects it at
+    // a t² + 2b t + c = 0. With q = -b - sign(b) √Δ, the roots are c / q and q / a, which does not
+    // lose precision when the curvature goes to zero.
+    let weights = Vec3::new(1.0, 1.0, 1.0 + k);
+
+    let a = c * (weights * direction).dot(direction);
+    let b = c * (weights * origin).dot(direction) - direction.z;
+    let c = c * (weights * origin).dot(origin) - 2.0 * origin.z;
+
+    let discriminant = b * b - a * c;
+
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let q = -b - b.signum() * discriminant.sqrt();
+
+    [c / q, q / a]
+        .into_iter()
+        .filter(|t| t.is_finite() && *t >= 0.0)
+        .min_by(|t1, t2| {
+            let z1 = (origin.z + direction.z * t1).abs();
+            let z2 = (origin.z + direction.z * t2).abs();
+
+            z1.total_cmp(&z2)
+        })
+}
+
 pub fn intersect(
     data: &SurfaceData,
     refracted_ray: &RefractedRay,
-    transform: &Mat4,
-) -> Intersection {
+    placement: &Placement,
+) -> Option<Intersection> {
     let curvature: f32 = data[CURVATURE].into();
+    let conic: f32 = data[CONIC].into();
 
-    if curvature == 0.0 {
-        todo!();
-        /*
-        return Intersection {
-            ray: ray.clone(),
-            normal: Vec3::NEG_Z,
-            t: (z - ray.origin.z) / ray.direction.z,
-        };
-        */
-    }
+    let (origin, direction) = local_ray(refracted_ray, placement);
+    let t = intersect_conic(origin, direction, curvature, conic)?;
+
+    let point = origin + direction * t;
+    let normal = Vec3::new(
+        curvature * point.x,
+        curvature * point.y,
+        curvature * (1.0 + conic) * point.z - 1.0,
+    );
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ray::Ray,
+        surface::{Surface, SurfaceKind},
+        system::System,
+    };
+
+    /// Concave surfaces must be hit near their vertex even when rays start farther away than
+    /// their radius, where the far side of the sphere is also ahead of the ray.
+    #[test]
+    fn concave_surface_beyond_radius() {
+        let curvature = -1.0 / 30.0;
 
-    let radius = curvature.recip();
-    // perhaps this could be changed to transform_point3
-    let center = transform.project_point3(radius * Vec3::Z);
+        for y in [0.0, 1.0, 5.0] {
+            let origin = Vec3::new(0.0, y, -50.0);
+            let t = intersect_conic(origin, Vec3::Z, curvature, 0.0).unwrap();
+            let expected = 50.0 + conic_sag(curvature, 0.0, y * y);
 
-    let a = 1.0; // 1.0 because ray.direction is normalized
-    let b = 2.0 * refracted_ray.direction.dot(refracted_ray.origin - center);
-    let c = (refracted_ray.origin - center).dot(refracted_ray.origin - center) - radius * radius;
+            assert!(
+                (t - expected).abs() < 1e-4,
+                "y = {y}: t = {t}, expected {expected}"
+            );
+        }
 
-    let delta = (b * b - 4.0 * a * c).sqrt();
+        let mut system = System::default();
+        *<&mut f32>::from(&mut system.surfaces[0].data_mut()[THICKNESS]) = 50.0;
+        *<&mut f32>::from(&mut system.surfaces[1].data_mut()[CURVATURE]) = curvature;
 
-    let t1 = (-b - delta) / (2.0 * a);
-    let t2 = (-b + delta) / (2.0 * a);
+        for y in [0.0, 1.0, 5.0] {
+            let trace = system.trace(Ray::new(Vec3::new(0.0, y, -50.0), Vec3::Z, 0.5876));
+            let point = trace.records[1].point;
 
-    let s = radius.signum();
-    let t = if radius < 0.0 { t2 } else { t1 };
-    let normal = (refracted_ray.direction * t + refracted_ray.origin - center).normalize() * s;
+            assert!(point.z.abs() < 1.0, "y = {y}: hit at {point}");
+        }
+    }
+
+    /// Collimated rays start on the vertex plane of the first surface, so they have to be moved
+    /// back to reach concave surfaces, even without a semi-diameter or behind a coordinate break.
+    #[test]
+    fn collimated_rays_reach_concave_surface() {
+        let curvature = -1.0 / 30.0;
+        let system = System {
+            surfaces: vec![
+                Surface::new(
+                    SurfaceKind::Object,
+                    SurfaceData::default().with(THICKNESS, f32::INFINITY),
+                ),
+                Surface::new(SurfaceKind::CoordinateBreak, SurfaceData::default()),
+                Surface::new(
+                    SurfaceKind::Spherical,
+                    SurfaceData::default()
+                        .with(THICKNESS, 50.0)
+                        .with(CURVATURE, curvature),
+                ),
+                Surface::new(SurfaceKind::Image, SurfaceData::default()),
+            ],
+            ..System::default()
+        };
+
+        for y in [1.0, 5.0] {
+            let trace = system.trace(Ray::new(Vec3::new(0.0, y, 0.0), Vec3::Z, 0.5876));
+            let point = trace.records[2].point;
+            let expected = conic_sag(curvature, 0.0, y * y);
 
-    return Intersection { normal, t };
+            assert!(trace.is_complete(), "y = {y}: {:?}", trace.failure);
+            assert!((point.z - expected).abs() < 1e-4, "y = {y}: hit at {point}");
+        }
+    }
+
+    /// Roots behind the origin of the ray are not intersections.
+    #[test]
+    fn rejects_intersections_behind_ray() {
+        assert!(intersect_conic(Vec3::new(0.0, 0.0, 30.0), Vec3::Z, 1.0 / 10.0, 0.0).is_none());
+    }
 }
@@ -0,0 +1,91 @@
+#[cfg(target_arch = "spirv")]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    glam::Vec2,
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{
+        CONIC, CONIC_X, CURVATURE, CURVATURE_X, Placement, SurfaceData,
+        kind::{intersect_sag, local_ray, spherical},
+    },
+};
+
+// A toroid is the conic on the YZ plane, with curvature `CURVATURE` and conic constant `CONIC`,
+// swept along a second conic on the XZ plane, with curvature `CURVATURE_X` and conic constant
+// `CONIC_X`, whose vertex follows the YZ profile. The XZ conic is centered on an axis parallel to Y
+// 1 / `CURVATURE_X` away from the vertex, so its curvature at a height y is `CURVATURE_X` / (1 -
+// `CURVATURE_X` z(y)). When `CONIC_X` is zero, this is the YZ profile rotated around that axis.
+// Setting either curvature to zero gives a cylinder.
+
+/// Sag of the surface at the local point (x, y), or `None` outside of the surface.
+pub fn sag(data: &SurfaceData, x: f32, y: f32) -> Option<f32> {
+    sag_and_gradient(data, Vec2::new(x, y)).map(|(sag, _)| sag)
+}
+
+fn sag_and_gradient(data: &SurfaceData, point: Vec2) -> Option<(f32, Vec2)> {
+    let curvature_x: f32 = data[CURVATURE_X].into();
+    let curvature_y: f32 = data[CURVATURE].into();
+    let conic_x: f32 = data[CONIC_X].into();
+    let conic_y: f32 = data[CONIC].into();
+
+    let Vec2 { x, y } = point;
+
+    if (1.0 + conic_y) * curvature_y * curvature_y * y * y > 1.0 {
+        return None;
+    }
+
+    // Sag of the YZ profile and its derivative
+    let profile = spherical::conic_sag(curvature_y, conic_y, y * y);
+    let profile_slope = y * spherical::conic_slope(curvature_y, conic_y, y * y);
+
+    // Curvature of the XZ section through the profile, and its derivative along y
+    let section = curvature_x / (1.0 - curvature_x * profile);
+    let section_slope = section * section * profile_slope;
+
+    let root = 1.0 - (1.0 + conic_x) * section * section * x * x;
+
+    if !section.is_finite() || root < 0.0 {
+        return None;
+    }
+
+    let section_sag = spherical::conic_sag(section, conic_x, x * x);
+
+    // Derivative of the conic sag with respect to its curvature, from differentiating
+    // c (x² + (1 + k) z²) = 2z
+    let curvature_derivative =
+        (x * x + (1.0 + conic_x) * section_sag * section_sag) / (2.0 * root.sqrt());
+
+    let sag = profile + section_sag;
+    let gradient = Vec2::new(
+        x * spherical::conic_slope(section, conic_x, x * x),
+        profile_slope + curvature_derivative * section_slope,
+    );
+
+    Some((sag, gradient))
+}
+
+pub fn intersect(
+    data: &SurfaceData,
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+
+    let start = spherical::intersect_conic(
+        origin,
+        direction,
+        data[CURVATURE].into(),
+        data[CONIC].into(),
+    )
+    .unwrap_or(-origin.z / direction.z);
+
+    let (t, normal) = intersect_sag(origin, direction, start, |point| {
+        sag_and_gradient(data, point)
+    })?;
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
@@ -0,0 +1,151 @@
+#[cfg(target_arch = "spirv")]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    glam::Vec2,
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{
+        CONIC, CURVATURE, Meridian, NORM_RADIUS, Placement, SurfaceData,
+        kind::{intersect_sag, local_ray, spherical},
+    },
+};
+
+// Base conic plus Σ c_ij x^i y^j, evaluated at (x, y) / `NORM_RADIUS`. Coefficients are sorted
+// by total degree and then by decreasing power of x, so they start with x, y, x², xy, y², x³...
+// Terms are ignored until the normalization radius is set to a positive value.
+
+/// Highest total degree of the polynomial.
+pub const MAX_DEGREE: u32 = 10;
+
+/// Number of terms of the polynomial, from degree 1 up to [`MAX_DEGREE`].
+pub const MAX_TERMS: usize = ((MAX_DEGREE * (MAX_DEGREE + 3)) / 2) as usize;
+
+/// Powers of x and y multiplied by the coefficient at `index`, or `None` past [`MAX_TERMS`].
+pub const fn exponents(index: usize) -> Option<(u32, u32)> {
+    if index >= MAX_TERMS {
+        return None;
+    }
+
+    // Degree p has p + 1 terms
+    let mut degree = 1;
+    let mut first = 0;
+
+    while index > first + degree as usize {
+        first += degree as usize + 1;
+        degree += 1;
+    }
+
+    let j = (index - first) as u32;
+
+    Some((degree - j, j))
+}
+
+/// Coefficients that take part in the sag, which are none without a normalization radius.
+fn terms<'c>(data: &SurfaceData, coefficients: &'c [f32]) -> &'c [f32] {
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+
+    if norm_radius > 0.0 {
+        &coefficients[..coefficients.len().min(MAX_TERMS)]
+    } else {
+        &[]
+    }
+}
+
+/// Sag of the surface at the local point (x, y), or `None` outside of the base conic.
+pub fn sag(data: &SurfaceData, coefficients: &[f32], x: f32, y: f32) -> Option<f32> {
+    sag_and_gradient(data, coefficients, Vec2::new(x, y)).map(|(sag, _)| sag)
+}
+
+/// Curvature on `meridian` of the surface near its vertex.
+pub fn paraxial_curvature(data: &SurfaceData, coefficients: &[f32], meridian: Meridian) -> f32 {
+    let curvature: f32 = data[CURVATURE].into();
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+
+    let square = match meridian {
+        Meridian::X => (2, 0),
+        Meridian::Y => (0, 2),
+    };
+
+    terms(data, coefficients)
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| exponents(index) == Some(square))
+        .fold(curvature, |sum, (_, coefficient)| {
+            sum + 2.0 * coefficient / (norm_radius * norm_radius)
+        })
+}
+
+fn sag_and_gradient(data: &SurfaceData, coefficients: &[f32], point: Vec2) -> Option<(f32, Vec2)> {
+    let curvature: f32 = data[CURVATURE].into();
+    let conic: f32 = data[CONIC].into();
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let r2 = point.length_squared();
+
+    if (1.0 + conic) * curvature * curvature * r2 > 1.0 {
+        return None;
+    }
+
+    let mut sag = spherical::conic_sag(curvature, conic, r2);
+    let mut gradient = point * spherical::conic_slope(curvature, conic, r2);
+
+    let Vec2 { x, y } = point / norm_radius;
+
+    // powers[i] holds (x^i, y^i)
+    let mut powers = [Vec2::ONE; MAX_DEGREE as usize + 1];
+
+    for i in 1..powers.len() {
+        powers[i] = powers[i - 1] * Vec2::new(x, y);
+    }
+
+    for (index, &coefficient) in terms(data, coefficients).iter().enumerate() {
+        let Some((i, j)) = exponents(index) else {
+            break;
+        };
+
+        let (i, j) = (i as usize, j as usize);
+
+        sag += coefficient * powers[i].x * powers[j].y;
+
+        let dx = if i > 0 {
+            i as f32 * powers[i - 1].x * powers[j].y
+        } else {
+            0.0
+        };
+        let dy = if j > 0 {
+            j as f32 * powers[i].x * powers[j - 1].y
+        } else {
+            0.0
+        };
+
+        gradient += coefficient * Vec2::new(dx, dy) / norm_radius;
+    }
+
+    Some((sag, gradient))
+}
+
+pub fn intersect(
+    data: &SurfaceData,
+    coefficients: &[f32],
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+
+    let start = spherical::intersect_conic(
+        origin,
+        direction,
+        data[CURVATURE].into(),
+        data[CONIC].into(),
+    )
+    .unwrap_or(-origin.z / direction.z);
+
+    let (t, normal) = intersect_sag(origin, direction, start, |point| {
+        sag_and_gradient(data, coefficients, point)
+    })?;
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
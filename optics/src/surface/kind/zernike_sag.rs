@@ -0,0 +1,104 @@
+#[cfg(target_arch = "spirv")]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    glam::Vec2,
+    intersection::Intersection,
+    refracted_ray::RefractedRay,
+    surface::{
+        CONIC, CURVATURE, Meridian, NORM_RADIUS, Placement, SurfaceData, ZERNIKE_ORDERING,
+        kind::{intersect_sag, local_ray, spherical},
+    },
+    zernike::ZernikeOrdering,
+};
+
+// Base conic plus a sum of Zernike polynomials, where `coefficients[j - 1]` is the sag of the j-th
+// term and the polynomials are evaluated at (x, y) / `NORM_RADIUS`. Terms are ignored until the
+// normalization radius is set to a positive value.
+
+fn ordering(data: &SurfaceData) -> ZernikeOrdering {
+    ZernikeOrdering::from_u32(data[ZERNIKE_ORDERING].into())
+}
+
+/// Coefficients that take part in the sag, which are none without a normalization radius.
+fn terms<'c>(data: &SurfaceData, coefficients: &'c [f32]) -> &'c [f32] {
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+
+    if norm_radius > 0.0 { coefficients } else { &[] }
+}
+
+/// Sag of the surface at the local point (x, y), or `None` outside of the base conic.
+pub fn sag(data: &SurfaceData, coefficients: &[f32], x: f32, y: f32) -> Option<f32> {
+    sag_and_gradient(data, coefficients, Vec2::new(x, y)).map(|(sag, _)| sag)
+}
+
+/// Curvature on `meridian` of the surface near its vertex.
+pub fn paraxial_curvature(data: &SurfaceData, coefficients: &[f32], meridian: Meridian) -> f32 {
+    let curvature: f32 = data[CURVATURE].into();
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let ordering = ordering(data);
+
+    (1..)
+        .zip(terms(data, coefficients))
+        .filter_map(|(j, coefficient)| {
+            let term = ordering.term(j)?;
+            Some(coefficient * term.paraxial_curvature(meridian) / (norm_radius * norm_radius))
+        })
+        .fold(curvature, |sum, curvature| sum + curvature)
+}
+
+fn sag_and_gradient(data: &SurfaceData, coefficients: &[f32], point: Vec2) -> Option<(f32, Vec2)> {
+    let curvature: f32 = data[CURVATURE].into();
+    let conic: f32 = data[CONIC].into();
+    let norm_radius: f32 = data[NORM_RADIUS].into();
+    let ordering = ordering(data);
+    let r2 = point.length_squared();
+
+    if (1.0 + conic) * curvature * curvature * r2 > 1.0 {
+        return None;
+    }
+
+    let mut sag = spherical::conic_sag(curvature, conic, r2);
+    let mut gradient = point * spherical::conic_slope(curvature, conic, r2);
+
+    let normalized = point / norm_radius;
+
+    for (j, &coefficient) in (1..).zip(terms(data, coefficients)) {
+        let Some(term) = ordering.term(j) else {
+            break;
+        };
+
+        let (value, term_gradient) = term.value_and_gradient(normalized);
+
+        sag += coefficient * value;
+        gradient += coefficient * term_gradient / norm_radius;
+    }
+
+    Some((sag, gradient))
+}
+
+pub fn intersect(
+    data: &SurfaceData,
+    coefficients: &[f32],
+    refracted_ray: &RefractedRay,
+    placement: &Placement,
+) -> Option<Intersection> {
+    let (origin, direction) = local_ray(refracted_ray, placement);
+
+    let start = spherical::intersect_conic(
+        origin,
+        direction,
+        data[CURVATURE].into(),
+        data[CONIC].into(),
+    )
+    .unwrap_or(-origin.z / direction.z);
+
+    let (t, normal) = intersect_sag(origin, direction, start, |point| {
+        sag_and_gradient(data, coefficients, point)
+    })?;
+
+    Some(Intersection {
+        normal: placement.transform.transform_vector3(normal).normalize(),
+        t,
+    })
+}
@@ -1,21 +1,70 @@
+mod aperture;
 mod data;
 mod field;
 mod kind;
 
 // pub use coordinate_break::CoordinateBreak;
+pub use aperture::*;
 pub use data::SurfaceData;
 pub use field::*;
-use glam::Mat4;
+use glam::{Mat4, Vec2};
 pub use kind::*;
 
-use crate::{prelude::Intersection, refracted_ray::RefractedRay};
+use crate::{
+    material::MaterialIndex,
+    prelude::Intersection,
+    refracted_ray::{RayFailure, RefractedRay, RefractiveIndex},
+};
+
+/// Plane that contains the optical axis, used to describe surfaces without rotational symmetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meridian {
+    /// The XZ plane, also known as sagittal plane.
+    X,
+    /// The YZ plane, also known as tangential plane.
+    Y,
+}
+
+/// Ray leaving a surface, together with the optical path the surface itself adds to it.
+#[derive(Clone)]
+pub struct Deflection {
+    pub ray: RefractedRay,
+    pub optical_path: f32,
+}
+
+/// Position and orientation of a surface, together with the inverse transform so that it is only
+/// computed once per surface.
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    /// Transform from the local coordinates of the surface to global coordinates.
+    pub transform: Mat4,
+    /// Transform from global coordinates to the local coordinates of the surface.
+    pub inverse: Mat4,
+}
+
+impl Placement {
+    pub fn new(transform: Mat4) -> Self {
+        Self {
+            transform,
+            inverse: transform.inverse(),
+        }
+    }
+}
 
 /// Surfaces are arrays of u32. Each SurfaceKind must be responsible for its own data logic.
-#[derive(Debug, Clone)]
+///
+/// Kinds that need more parameters than `SurfaceData` can hold, such as polynomial surfaces, read
+/// them from a block of coefficients whose length is not fixed.
+///
+/// Apertures limit the region of the surface that rays can go through. Surfaces without
+/// apertures let every ray that hits them through, whatever their semi-diameter is.
+#[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub struct Surface {
     pub(crate) kind: SurfaceKind,
     pub(crate) data: SurfaceData,
+    pub(crate) coefficients: Vec<f32>,
+    pub(crate) apertures: Vec<Aperture>,
 }
 
 impl Default for Surface {
@@ -26,7 +75,19 @@ impl Default for Surface {
 
 impl Surface {
     pub const fn new(kind: SurfaceKind, data: SurfaceData) -> Self {
-        Self { kind, data }
+        Self {
+            kind,
+            data,
+            coefficients: Vec::new(),
+            apertures: Vec::new(),
+        }
+    }
+
+    pub fn with_coefficients(self, coefficients: impl Into<Vec<f32>>) -> Self {
+        Self {
+            coefficients: coefficients.into(),
+            ..self
+        }
     }
 
     pub const fn kind(&self) -> SurfaceKind {
@@ -41,12 +102,193 @@ impl Surface {
         &mut self.data
     }
 
-    pub fn intersect(&self, ray: &RefractedRay, transform: &Mat4) -> Intersection {
+    pub const fn coefficients(&self) -> &Vec<f32> {
+        &self.coefficients
+    }
+
+    pub const fn coefficients_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.coefficients
+    }
+
+    pub const fn apertures(&self) -> &Vec<Aperture> {
+        &self.apertures
+    }
+
+    pub const fn apertures_mut(&mut self) -> &mut Vec<Aperture> {
+        &mut self.apertures
+    }
+
+    /// Whether a ray that hits the surface at the local point (x, y) goes through all of its
+    /// apertures.
+    pub fn passes_apertures(&self, x: f32, y: f32) -> bool {
+        let point = Vec2::new(x, y);
+
+        self.apertures.iter().all(|aperture| aperture.passes(point))
+    }
+
+    /// Changes the kind of the surface. Fields shared by both kinds keep their values. The rest are
+    /// kept too, but ignored until the surface changes back to a kind that has them.
+    pub const fn set_kind(&mut self, kind: SurfaceKind) {
+        self.kind = kind;
+    }
+
+    /// Material of the medium after the surface, or `None` when it is the same as before it. Kinds
+    /// without a material, such as paraxial surfaces and coordinate breaks, never change it.
+    pub fn material_index(&self) -> Option<MaterialIndex> {
+        self.kind.fields()[MATERIAL_INDEX].and(self.data[MATERIAL_INDEX].into())
+    }
+
+    /// Whether the surface reflects light instead of refracting it.
+    pub fn is_mirror(&self) -> bool {
+        match self.kind {
+            SurfaceKind::Spherical
+            | SurfaceKind::EvenAsphere
+            | SurfaceKind::Toroidal
+            | SurfaceKind::ZernikeSag
+            | SurfaceKind::XyPolynomial
+            | SurfaceKind::Biconic
+            | SurfaceKind::Grating => u32::from(self.data[MIRROR]) != 0,
+            _ => false,
+        }
+    }
+
+    /// Distance, along the local Z axis, between this surface and the next one.
+    pub fn thickness(&self) -> f32 {
+        match self.kind {
+            SurfaceKind::Spherical
+            | SurfaceKind::EvenAsphere
+            | SurfaceKind::Paraxial
+            | SurfaceKind::Toroidal
+            | SurfaceKind::ZernikeSag
+            | SurfaceKind::XyPolynomial
+            | SurfaceKind::Biconic
+            | SurfaceKind::Grating
+            | SurfaceKind::Binary2 => self.data[THICKNESS].into(),
+            SurfaceKind::CoordinateBreak => self.data[TRANSLATION_Z].into(),
+            SurfaceKind::Object | SurfaceKind::Image => 0.0,
+        }
+    }
+
+    /// Transformation from the coordinate system of the next surface to the one of this surface.
+    pub fn transformation_matrix(&self) -> Mat4 {
+        match self.kind {
+            SurfaceKind::Spherical
+            | SurfaceKind::EvenAsphere
+            | SurfaceKind::Paraxial
+            | SurfaceKind::Toroidal
+            | SurfaceKind::ZernikeSag
+            | SurfaceKind::XyPolynomial
+            | SurfaceKind::Biconic
+            | SurfaceKind::Grating
+            | SurfaceKind::Binary2 => spherical::transformation_matrix(&self.data),
+            SurfaceKind::CoordinateBreak => coordinate_break::transformation_matrix(&self.data),
+            SurfaceKind::Object | SurfaceKind::Image => Mat4::IDENTITY,
+        }
+    }
+
+    /// Sag of the surface at the local point (x, y), for kinds that are described by one.
+    pub fn sag(&self, x: f32, y: f32) -> Option<f32> {
+        let r = x.hypot(y);
+
+        match self.kind {
+            SurfaceKind::Spherical | SurfaceKind::Grating | SurfaceKind::Binary2 => {
+                Some(spherical::sag(&self.data, r))
+            }
+            SurfaceKind::EvenAsphere => Some(even_asphere::sag(&self.data, r)),
+            SurfaceKind::Toroidal => toroidal::sag(&self.data, x, y),
+            SurfaceKind::Biconic => biconic::sag(&self.data, x, y),
+            SurfaceKind::ZernikeSag => zernike_sag::sag(&self.data, &self.coefficients, x, y),
+            SurfaceKind::XyPolynomial => xy_polynomial::sag(&self.data, &self.coefficients, x, y),
+            SurfaceKind::Image | SurfaceKind::Paraxial => Some(0.0),
+            SurfaceKind::CoordinateBreak | SurfaceKind::Object => None,
+        }
+    }
+
+    /// Curvature on `meridian` used by paraxial calculations, for kinds that have optical power.
+    pub fn paraxial_curvature(&self, meridian: Meridian) -> Option<f32> {
+        match self.kind {
+            SurfaceKind::Spherical | SurfaceKind::Grating | SurfaceKind::Binary2 => {
+                Some(self.data[CURVATURE].into())
+            }
+            SurfaceKind::EvenAsphere => Some(even_asphere::paraxial_curvature(&self.data)),
+            SurfaceKind::Toroidal | SurfaceKind::Biconic => Some(match meridian {
+                Meridian::X => self.data[CURVATURE_X].into(),
+                Meridian::Y => self.data[CURVATURE].into(),
+            }),
+            SurfaceKind::ZernikeSag => Some(zernike_sag::paraxial_curvature(
+                &self.data,
+                &self.coefficients,
+                meridian,
+            )),
+            SurfaceKind::XyPolynomial => Some(xy_polynomial::paraxial_curvature(
+                &self.data,
+                &self.coefficients,
+                meridian,
+            )),
+            SurfaceKind::Image
+            | SurfaceKind::CoordinateBreak
+            | SurfaceKind::Object
+            | SurfaceKind::Paraxial => None,
+        }
+    }
+
+    /// Intersects `ray` with the surface placed at `placement`. Returns `None` when the ray misses it.
+    pub fn intersect(&self, ray: &RefractedRay, placement: &Placement) -> Option<Intersection> {
+        match self.kind {
+            SurfaceKind::Spherical | SurfaceKind::Grating | SurfaceKind::Binary2 => {
+                spherical::intersect(&self.data, ray, placement)
+            }
+            SurfaceKind::CoordinateBreak => coordinate_break::intersect(&self.data, ray, placement),
+            SurfaceKind::Image => image::intersect(&self.data, ray, placement),
+            SurfaceKind::Object => object::intersect(&self.data, ray, placement),
+            SurfaceKind::EvenAsphere => even_asphere::intersect(&self.data, ray, placement),
+            SurfaceKind::Paraxial => paraxial::intersect(&self.data, ray, placement),
+            SurfaceKind::Toroidal => toroidal::intersect(&self.data, ray, placement),
+            SurfaceKind::Biconic => biconic::intersect(&self.data, ray, placement),
+            SurfaceKind::ZernikeSag => {
+                zernike_sag::intersect(&self.data, &self.coefficients, ray, placement)
+            }
+            SurfaceKind::XyPolynomial => {
+                xy_polynomial::intersect(&self.data, &self.coefficients, ray, placement)
+            }
+        }
+    }
+
+    /// Deflects `ray` at `intersection`, into a medium with index `refractive_index`. Mirrors
+    /// reflect the ray back into the medium it came from.
+    pub fn deflect(
+        &self,
+        ray: &RefractedRay,
+        intersection: &Intersection,
+        placement: &Placement,
+        refractive_index: RefractiveIndex,
+    ) -> Result<Deflection, RayFailure> {
         match self.kind {
-            SurfaceKind::Spherical => spherical::intersect(&self.data, ray, transform),
-            SurfaceKind::CoordinateBreak => todo!(),
-            SurfaceKind::Image => todo!(),
-            SurfaceKind::Object => todo!(),
+            SurfaceKind::Paraxial => paraxial::deflect(&self.data, ray, intersection, placement),
+            SurfaceKind::Grating => grating::deflect(
+                &self.data,
+                ray,
+                intersection,
+                placement,
+                refractive_index,
+                self.is_mirror(),
+            ),
+            SurfaceKind::Binary2 => binary2::deflect(
+                &self.data,
+                &self.coefficients,
+                ray,
+                intersection,
+                placement,
+                refractive_index,
+            ),
+            _ if self.is_mirror() => Ok(Deflection {
+                ray: ray.reflect(intersection),
+                optical_path: 0.0,
+            }),
+            _ => Ok(Deflection {
+                ray: ray.refract(intersection, refractive_index)?,
+                optical_path: 0.0,
+            }),
         }
     }
 }
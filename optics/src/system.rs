@@ -1,19 +1,30 @@
-use glam::{Mat4, vec3};
+use glam::{Mat2, Mat4, Vec2, Vec3};
 
 use crate::{
-    material::{Formula, Material},
+    fields::{FieldPoint, FieldType},
+    first_order::{ParaxialModel, ParaxialSurface},
+    grin::Medium,
+    material::{Formula, Gradient, Material},
     prelude::MaterialIndex,
-    ray::Wavelength,
+    pupil::RayAiming,
+    ray::{Ray, Wavelength},
+    refracted_ray::{RayFailure, RefractedRay, RefractiveIndex},
     surface::*,
+    trace::{Trace, TraceRecord},
+    wavelengths::{DEFAULT_WAVELENGTH, WeightedWavelength},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct System {
     pub surfaces: Vec<Surface>,
     pub materials: Vec<Material>,
     pub stop_index: u32,
+    pub ray_aiming: RayAiming,
     pub medium: MaterialIndex,
-    pub wavelengths: Vec<Wavelength>,
+    pub wavelengths: Vec<WeightedWavelength>,
+    pub primary_wavelength: usize,
+    pub field_type: FieldType,
+    pub fields: Vec<FieldPoint>,
 }
 
 impl System {
@@ -21,23 +32,54 @@ impl System {
         self.materials.get(index.get() as usize - 1)
     }
 
+    /// Refractive index of the medium after `surface`, where `n` is the index before it.
+    /// Mirrors send light back into the medium it came from, so their material is ignored.
+    fn refractive_index_after(
+        &self,
+        surface: &Surface,
+        n: RefractiveIndex,
+        wavelength: Wavelength,
+    ) -> RefractiveIndex {
+        if surface.is_mirror() {
+            return n;
+        }
+
+        match surface.material_index() {
+            Some(material_index) => self
+                .material(material_index)
+                .expect("Surface has an undefined material")
+                .refractive_index(wavelength),
+            None => n,
+        }
+    }
+
+    /// Index profile of the medium after `surface`, where `gradient` is the one before it. Returns
+    /// `None` for homogeneous media.
+    fn gradient_after<'s>(
+        &'s self,
+        surface: &Surface,
+        gradient: Option<&'s Gradient>,
+    ) -> Option<&'s Gradient> {
+        if surface.is_mirror() {
+            return gradient;
+        }
+
+        match surface.material_index() {
+            Some(material_index) => self
+                .material(material_index)
+                .expect("Surface has an undefined material")
+                .gradient(),
+            None => gradient,
+        }
+    }
+
     pub fn surfaces(&self) -> impl Iterator<Item = (&Surface, Mat4)> {
         self.surfaces.iter().map({
             let mut transform = Mat4::IDENTITY;
 
             move |surface| {
                 let old_transform = transform;
-
-                match surface.kind() {
-                    SurfaceKind::Spherical => {
-                        let data = surface.data();
-                        transform *= Mat4::from_translation(vec3(0.0, 0.0, data[THICKNESS].into()));
-                    }
-                    SurfaceKind::CoordinateBreak => {
-                        transform *= coordinate_break::transformation_matrix(surface.data());
-                    }
-                    _ => {}
-                }
+                transform *= surface.transformation_matrix();
 
                 (surface, old_transform)
             }
@@ -45,67 +87,289 @@ impl System {
     }
 }
 
+// Implementation of ray tracing methods
+impl System {
+    /// Traces `ray` sequentially through every surface of the system.
+    ///
+    /// The ray starts at the object surface, so its origin and direction must be given in global
+    /// coordinates. Ray direction does not need to be normalized.
+    pub fn trace(&self, ray: Ray) -> Trace {
+        self.trace_until(ray, usize::MAX, true)
+    }
+
+    /// Distance that a collimated ray starting at `origin` towards `direction` is moved back, so
+    /// that it starts in front of the first surface with a sag.
+    ///
+    /// Collimated rays start on a plane through the global origin, which concave surfaces and large
+    /// field angles reach behind. Surfaces are assumed to be no deeper than their semi-diameter, or
+    /// than their sag where the ray starts when they have none. Moving every ray of a field along
+    /// its direction keeps them in phase.
+    fn launch_distance(&self, origin: Vec3, direction: Vec3) -> f32 {
+        let Some((surface, transform)) = self
+            .surfaces()
+            .skip(1)
+            .find(|(surface, _)| surface.sag(0.0, 0.0).is_some())
+        else {
+            return 0.0;
+        };
+
+        let placement = Placement::new(transform);
+        let origin = placement.inverse.transform_point3(origin);
+        let direction = placement.inverse.transform_vector3(direction);
+
+        if direction.z <= 0.0 {
+            return 0.0;
+        }
+
+        let semi_diameter: f32 = surface.data[SEMI_DIAMETER].into();
+        let depth = if surface.kind.fields()[SEMI_DIAMETER].is_some() && semi_diameter > 0.0 {
+            semi_diameter
+        } else {
+            surface.sag(origin.x, origin.y).map_or(0.0, |sag| -sag)
+        };
+
+        ((origin.z + depth.max(0.0)) / direction.z).max(0.0)
+    }
+
+    /// Traces `ray` up to the surface at index `last`, checking apertures only when `clip` is
+    /// set.
+    pub(crate) fn trace_until(&self, ray: Ray, last: usize, clip: bool) -> Trace {
+        let wavelength = ray.wavelength;
+        let mut records = Vec::with_capacity(self.surfaces.len());
+        // Inverse transforms are needed at every step, so they are computed once per surface
+        let mut surfaces = self
+            .surfaces()
+            .map(|(surface, transform)| (surface, Placement::new(transform)));
+
+        let Some((object, object_placement)) = surfaces.next() else {
+            return Trace::default();
+        };
+
+        // Index of the medium the ray is traveling through. In gradient-index media, this is the
+        // index on the vertex of the surface before it.
+        let mut base = self.refractive_index_after(
+            object,
+            self.material(self.medium)
+                .expect("System medium is undefined")
+                .refractive_index(wavelength),
+            wavelength,
+        );
+        let mut gradient = self.gradient_after(object, None);
+        let mut medium = gradient.map(|gradient| Medium::new(gradient, base, &object_placement));
+
+        let direction = ray.direction.normalize();
+        let distance: f32 = object.data[THICKNESS].into();
+
+        let origin = if distance.is_finite() {
+            ray.origin
+        } else {
+            ray.origin - direction * self.launch_distance(ray.origin, direction)
+        };
+
+        let n = match &medium {
+            Some(medium) => medium.refractive_index(origin),
+            None => base,
+        };
+
+        let mut ray = RefractedRay::new(Ray::new(origin, direction, wavelength), n);
+        let mut optical_path = 0.0;
+
+        records.push(TraceRecord {
+            point: ray.origin,
+            local_point: object_placement.inverse.transform_point3(ray.origin),
+            normal: Vec3::NEG_Z,
+            direction: ray.direction,
+            optical_path,
+            refractive_index: ray.refractive_index,
+        });
+
+        for (index, (surface, placement)) in (1..=last).zip(surfaces) {
+            // Rays follow curved paths inside GRIN media, up to the vicinity of the next surface
+            if let Some(medium) = &medium {
+                let Some((propagated, path)) =
+                    medium.propagate(&ray, |ray| surface.intersect(ray, &placement))
+                else {
+                    return Trace::failed(records, index, RayFailure::Miss);
+                };
+
+                ray = propagated;
+                optical_path += f64::from(path);
+            }
+
+            let Some(intersection) = surface.intersect(&ray, &placement) else {
+                return Trace::failed(records, index, RayFailure::Miss);
+            };
+
+            let local_point = placement.inverse.transform_point3(ray.at(intersection.t));
+
+            if clip && !surface.passes_apertures(local_point.x, local_point.y) {
+                return Trace::failed(records, index, RayFailure::Vignetted);
+            }
+
+            base = self.refractive_index_after(surface, base, wavelength);
+            gradient = self.gradient_after(surface, gradient);
+            medium = gradient.map(|gradient| Medium::new(gradient, base, &placement));
+
+            let n = match &medium {
+                Some(medium) => medium.refractive_index(ray.at(intersection.t)),
+                None => base,
+            };
+
+            let deflection = match surface.deflect(&ray, &intersection, &placement, n) {
+                Ok(deflection) => deflection,
+                Err(reason) => return Trace::failed(records, index, reason),
+            };
+
+            // Lengths are measured between the points in double precision, as the rounding of
+            // segments hundreds of millimeters long is a sizable fraction of a wave
+            let length = (deflection.ray.origin.as_dvec3() - ray.origin.as_dvec3())
+                .dot(ray.direction.as_dvec3().normalize());
+
+            optical_path +=
+                f64::from(ray.refractive_index) * length + f64::from(deflection.optical_path);
+            ray = deflection.ray;
+
+            records.push(TraceRecord {
+                point: ray.origin,
+                local_point,
+                normal: intersection.normal,
+                direction: ray.direction,
+                optical_path,
+                refractive_index: ray.refractive_index,
+            });
+        }
+
+        Trace {
+            records,
+            failure: None,
+        }
+    }
+}
+
 // Implementation of query methods
 impl System {
     pub fn thickness(&self) -> f32 {
         self.surfaces()
-            .map(|(surface, _)| match surface.kind() {
-                SurfaceKind::Spherical => surface.data()[THICKNESS].into(),
-                SurfaceKind::CoordinateBreak => surface.data()[TRANSLATION_Z].into(),
-                _ => 0.0,
-            })
+            .map(|(surface, _)| surface.thickness())
             .sum()
     }
 
-    /// Based on the equation
-    /// $D = P_0 + P_1 + P_0 P_1 d_{0,1} / n_{0,1}$
-    ///
+    /// Transform from the local coordinates of the image surface to global coordinates.
+    pub fn image_transform(&self) -> Mat4 {
+        self.surfaces()
+            .last()
+            .map_or(Mat4::IDENTITY, |(_, transform)| transform)
+    }
+
+    /// Power on the YZ plane, see [`System::meridian_power`].
     pub fn power(&self, wavelength: Wavelength) -> Option<f32> {
-        let mut material = self.material(self.medium).unwrap();
-        let mut n = material.refractive_index(wavelength);
+        self.meridian_power(wavelength, Meridian::Y)
+    }
+
+    /// Fraction of the light at `wavelength` that the diffractive surfaces of the system send into
+    /// the orders being traced. Returns `None` when none of them has a design wavelength.
+    pub fn diffraction_efficiency(&self, wavelength: Wavelength) -> Option<f32> {
+        self.surfaces
+            .iter()
+            .filter(|surface| surface.kind() == SurfaceKind::Binary2)
+            .filter_map(|surface| binary2::efficiency(surface.data(), wavelength))
+            .reduce(|total, efficiency| total * efficiency)
+    }
+
+    /// Paraxial power on `meridian`, see [`ParaxialModel::power`].
+    pub fn meridian_power(&self, wavelength: Wavelength, meridian: Meridian) -> Option<f32> {
+        Some(self.paraxial_model(wavelength, meridian)?.power())
+    }
 
+    /// Paraxial model of the system on `meridian`. Returns `None` when some surface is not
+    /// supported by paraxial calculations.
+    pub fn paraxial_model(
+        &self,
+        wavelength: Wavelength,
+        meridian: Meridian,
+    ) -> Option<ParaxialModel> {
         let mut surfaces = self.surfaces();
 
-        // Object
-        {
-            let (surface, _) = surfaces.next()?;
-            if let Some(material_index) = surface.data[MATERIAL_INDEX].into() {
-                material = self
-                    .material(material_index)
-                    .expect("Surface has an undefined material");
-                n = material.refractive_index(wavelength);
-            }
-        }
+        // Index of the medium after the last surface. In gradient-index media, this is the index
+        // on the vertex of that surface.
+        let (object, _) = surfaces.next()?;
+        let mut base = self.refractive_index_after(
+            object,
+            self.material(self.medium)
+                .unwrap()
+                .refractive_index(wavelength),
+            wavelength,
+        );
+        let mut gradient = self.gradient_after(object, None);
 
+        let mut model = ParaxialModel {
+            object_index: base,
+            surfaces: Vec::with_capacity(self.surfaces.len()),
+        };
         let mut thickness = 0.0;
-        let mut power = 0.0;
 
-        for (surface, _) in surfaces {
-            let prev_thickness = thickness;
-            let prev_n = n;
-            let prev_power = power;
+        // Light travels backwards after a mirror, where refractive indices (and thicknesses) are
+        // negative. This makes the power of a mirror (n' - n) c = -2 n c
+        let mut direction = 1.0;
 
-            thickness = surface.data[THICKNESS].into();
+        for (index, (surface, _)) in (1..).zip(surfaces) {
+            // Coordinate breaks have no power, they only move the surfaces that follow them
+            if surface.kind() == SurfaceKind::CoordinateBreak {
+                thickness += surface.thickness();
+                continue;
+            }
+
+            // Transfer from the previous surface, along with the index right before this one
+            let (transfer, prev_n) = match gradient {
+                // Gradient-index media are not supported in reversed space
+                Some(_) if direction < 0.0 => return None,
+                Some(gradient) => (
+                    gradient.paraxial_transfer(base, thickness),
+                    gradient.refractive_index(base, Vec3::Z * thickness),
+                ),
+                None => {
+                    let n = direction * base;
+                    (Mat2::from_cols(Vec2::X, Vec2::new(thickness / n, 1.0)), n)
+                }
+            };
 
-            // material_index = None means "same as previous surface"
-            if let Some(material_index) = surface.data[MATERIAL_INDEX].into() {
-                material = self
-                    .material(material_index)
-                    .expect("Surface has an undefined material");
-                n = material.refractive_index(wavelength);
+            if surface.is_mirror() {
+                direction = -direction;
             }
 
-            match surface.kind() {
-                SurfaceKind::Spherical => {
-                    let curvature: f32 = surface.data[CURVATURE].into();
-                    let curr_power = (n - prev_n) * curvature;
+            thickness = surface.thickness();
+            base = self.refractive_index_after(surface, base, wavelength);
+            gradient = self.gradient_after(surface, gradient);
+
+            let n = direction * base;
 
-                    power =
-                        prev_power + curr_power - prev_power * curr_power * prev_thickness / prev_n;
+            let power = match surface.kind() {
+                SurfaceKind::Spherical
+                | SurfaceKind::EvenAsphere
+                | SurfaceKind::Toroidal
+                | SurfaceKind::ZernikeSag
+                | SurfaceKind::XyPolynomial
+                | SurfaceKind::Biconic
+                | SurfaceKind::Grating => (n - prev_n) * surface.paraxial_curvature(meridian)?,
+                SurfaceKind::Paraxial => paraxial::power(&surface.data),
+                SurfaceKind::Binary2 => {
+                    (n - prev_n) * surface.paraxial_curvature(meridian)?
+                        + binary2::power(&surface.data, &surface.coefficients, wavelength)
                 }
-                SurfaceKind::Image => return Some(power),
+                SurfaceKind::Image => 0.0,
                 // Other kinds of surface are not supported yet for power calculations
                 _ => return None,
+            };
+
+            model.surfaces.push(ParaxialSurface {
+                index,
+                transfer,
+                power,
+                refractive_index: n,
+            });
+
+            if surface.kind() == SurfaceKind::Image {
+                return Some(model);
             }
         }
 
@@ -122,6 +386,8 @@ impl Default for System {
                     data: crate::surface::SurfaceData::default()
                         .with(THICKNESS, 100.0)
                         .with(SEMI_DIAMETER, 10.0),
+                    coefficients: Vec::new(),
+                    apertures: Vec::new(),
                 },
                 Surface {
                     kind: crate::surface::SurfaceKind::Spherical,
@@ -130,6 +396,8 @@ impl Default for System {
                         .with(THICKNESS, 10.0)
                         .with(CURVATURE, 1.0 / 100.0)
                         .with(SEMI_DIAMETER, 10.0),
+                    coefficients: Vec::new(),
+                    apertures: Vec::new(),
                 },
                 Surface {
                     kind: crate::surface::SurfaceKind::Spherical,
@@ -138,10 +406,14 @@ impl Default for System {
                         .with(THICKNESS, 10.0)
                         .with(CURVATURE, 1.0 / -100.0)
                         .with(SEMI_DIAMETER, 10.0),
+                    coefficients: Vec::new(),
+                    apertures: Vec::new(),
                 },
                 Surface {
                     kind: crate::surface::SurfaceKind::Image,
                     data: crate::surface::SurfaceData::default(),
+                    coefficients: Vec::new(),
+                    apertures: Vec::new(),
                 },
             ],
             materials: vec![
@@ -168,11 +440,59 @@ impl Default for System {
                         l: [0.0105795466, 0.0493226978, 112.405955],
                     },
                 ),
+                // Radial GRIN rod with a gradient constant of 0.3 mm⁻¹, n = n₀ (1 - g² r² / 2)
+                Material::new("GRIN Rod".to_string(), Formula::Constant { cte: 1.6 })
+                    .with_gradient(Gradient {
+                        radial: [-0.072, 0.0, 0.0],
+                        axial: [0.0; 3],
+                        step: 0.05,
+                    }),
             ],
 
-            stop_index: 0,
+            stop_index: 1,
+            ray_aiming: RayAiming::default(),
             medium: MaterialIndex::new(1).unwrap(),
-            wavelengths: vec![0.5875618],
+            wavelengths: vec![WeightedWavelength::new(DEFAULT_WAVELENGTH)],
+            primary_wavelength: 0,
+            field_type: FieldType::ObjectHeight,
+            fields: vec![
+                FieldPoint::new(Vec2::ZERO),
+                FieldPoint::new(Vec2::new(0.0, 7.0)),
+                FieldPoint::new(Vec2::new(0.0, 10.0)),
+            ],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Surfaces switched from a glass kind to one without a material keep the data of the glass, but
+    /// light must not go into it.
+    #[test]
+    fn ignores_materials_of_kinds_without_one() {
+        let wavelength = DEFAULT_WAVELENGTH;
+
+        let mut system = System::default();
+        system.surfaces[1].set_kind(SurfaceKind::Paraxial);
+        *<&mut f32>::from(&mut system.surfaces[1].data[FOCAL_LENGTH]) = 50.0;
+        *<&mut f32>::from(&mut system.surfaces[2].data[CURVATURE]) = 0.0;
+
+        let power = system.power(wavelength).unwrap();
+        assert!((power - 0.02).abs() < 1e-4, "power = {power}");
+
+        let mut system = System::default();
+        system.surfaces[1].set_kind(SurfaceKind::CoordinateBreak);
+
+        let direction = Vec3::new(0.0, 0.05, 1.0).normalize();
+        let trace = system.trace(Ray::new(Vec3::ZERO, direction, wavelength));
+        let record = trace.records[1];
+
+        assert!(
+            record.direction.normalize().abs_diff_eq(direction, 1e-6),
+            "{record:?}"
+        );
+        assert_eq!(record.refractive_index, trace.records[0].refractive_index);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    glam::Vec3,
+    refracted_ray::{RayFailure, RefractiveIndex},
+};
+
+/// State of a ray right after it interacted with a surface. All vectors are in global coordinates,
+/// except for `local_point`.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceRecord {
+    /// Point where the ray hit the surface.
+    pub point: Vec3,
+    /// Same as `point`, in the local coordinates of the surface.
+    pub local_point: Vec3,
+    /// Surface normal at `point`.
+    pub normal: Vec3,
+    /// Direction of the ray leaving the surface.
+    pub direction: Vec3,
+    /// Optical path accumulated from the object up to `point`. It is kept in double precision, as
+    /// optical path differences are tiny compared to the paths themselves.
+    pub optical_path: f64,
+    /// Refractive index of the medium the ray is leaving into.
+    pub refractive_index: RefractiveIndex,
+}
+
+/// Surface at which a ray stopped being traced, and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceFailure {
+    pub surface: usize,
+    pub reason: RayFailure,
+}
+
+/// Result of tracing a single ray through a [`System`](crate::system::System).
+///
+/// `records[i]` holds the state of the ray at `system.surfaces[i]`. When the ray fails at some
+/// surface, `records` stops right before that surface.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    pub records: Vec<TraceRecord>,
+    pub failure: Option<TraceFailure>,
+}
+
+impl Trace {
+    pub(crate) const fn failed(
+        records: Vec<TraceRecord>,
+        surface: usize,
+        reason: RayFailure,
+    ) -> Self {
+        Self {
+            records,
+            failure: Some(TraceFailure { surface, reason }),
+        }
+    }
+
+    pub const fn is_complete(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// Record at the last traced surface, which is the image surface when the trace is complete.
+    pub fn last(&self) -> Option<&TraceRecord> {
+        self.records.last()
+    }
+}
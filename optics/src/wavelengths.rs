@@ -0,0 +1,105 @@
+use crate::{ray::Wavelength, system::System};
+
+/// Helium d line, used when a system has no wavelengths.
+pub const DEFAULT_WAVELENGTH: Wavelength = 0.5875618;
+
+/// Wavelength of the spectrum of a system, along with its relative contribution to polychromatic
+/// analyses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedWavelength {
+    pub value: Wavelength,
+    pub weight: f32,
+}
+
+impl WeightedWavelength {
+    pub const fn new(value: Wavelength) -> Self {
+        Self { value, weight: 1.0 }
+    }
+}
+
+/// Common sets of wavelengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavelengthPreset {
+    /// Fraunhofer F, d and C lines, used to define the Abbe number.
+    FdC,
+    /// Evenly spaced wavelengths over the visible spectrum.
+    Visible,
+    /// Evenly spaced wavelengths over the near infrared.
+    NearInfrared,
+    /// Fundamental, second and third harmonics of Nd:YAG lasers.
+    NdYag,
+}
+
+impl WavelengthPreset {
+    pub const ALL: [Self; 4] = [Self::FdC, Self::Visible, Self::NearInfrared, Self::NdYag];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::FdC => "F, d, C",
+            Self::Visible => "Visible",
+            Self::NearInfrared => "NIR",
+            Self::NdYag => "Nd:YAG",
+        }
+    }
+
+    /// Wavelengths of the preset, in micrometers.
+    pub const fn wavelengths(&self) -> &'static [Wavelength] {
+        match self {
+            Self::FdC => &[0.4861327, 0.5875618, 0.6562725],
+            Self::Visible => &[0.45, 0.5, 0.55, 0.6, 0.65],
+            Self::NearInfrared => &[0.75, 0.85, 0.95],
+            Self::NdYag => &[1.064, 0.532, 0.355],
+        }
+    }
+
+    /// Index of the primary wavelength of the preset.
+    pub const fn primary(&self) -> usize {
+        match self {
+            Self::FdC | Self::NearInfrared => 1,
+            Self::Visible => 2,
+            Self::NdYag => 0,
+        }
+    }
+}
+
+// Implementation of wavelength methods
+impl System {
+    /// Wavelength used by monochromatic analyses, like paraxial quantities.
+    pub fn primary_wavelength(&self) -> Wavelength {
+        self.wavelengths
+            .get(self.primary_wavelength)
+            .or(self.wavelengths.first())
+            .map_or(DEFAULT_WAVELENGTH, |wavelength| wavelength.value)
+    }
+
+    /// Wavelengths of the system along with their weights, normalized to add up to one.
+    /// Polychromatic analyses combine the results of every wavelength with these weights.
+    pub fn weighted_wavelengths(&self) -> impl Iterator<Item = (Wavelength, f32)> {
+        let total: f32 = self
+            .wavelengths
+            .iter()
+            .map(|wavelength| wavelength.weight)
+            .sum();
+
+        self.wavelengths.iter().map(move |wavelength| {
+            let weight = if total > 0.0 {
+                wavelength.weight / total
+            } else {
+                0.0
+            };
+
+            (wavelength.value, weight)
+        })
+    }
+
+    /// Replaces the wavelengths of the system with the ones of `preset`.
+    pub fn set_wavelengths(&mut self, preset: WavelengthPreset) {
+        self.wavelengths = preset
+            .wavelengths()
+            .iter()
+            .copied()
+            .map(WeightedWavelength::new)
+            .collect();
+        self.primary_wavelength = preset.primary();
+    }
+}
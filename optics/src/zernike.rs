@@ -0,0 +1,300 @@
+use crate::{glam::Vec2, solver::solve_linear, surface::Meridian};
+
+/// Convention used to number Zernike polynomials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ZernikeOrdering {
+    /// University of Arizona (fringe) ordering, limited to 37 unnormalized terms.
+    Fringe = 0,
+    /// Noll ordering, where every term is normalized to unit RMS over the unit circle.
+    Standard = 1,
+}
+
+impl ZernikeOrdering {
+    pub const ALL: [Self; 2] = [Self::Fringe, Self::Standard];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Fringe => "Fringe",
+            Self::Standard => "Standard",
+        }
+    }
+
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Standard,
+            _ => Self::Fringe,
+        }
+    }
+
+    /// Number of terms defined by the ordering, if it is limited.
+    pub const fn max_terms(&self) -> Option<usize> {
+        match self {
+            Self::Fringe => Some(37),
+            Self::Standard => None,
+        }
+    }
+
+    /// The `j`-th term of the ordering, starting at 1. Returns `None` when it is not defined.
+    pub fn term(&self, j: usize) -> Option<ZernikeTerm> {
+        match self {
+            Self::Fringe => ZernikeTerm::fringe(j),
+            Self::Standard => ZernikeTerm::standard(j),
+        }
+    }
+}
+
+/// Zernike polynomial of radial order `n` and azimuthal frequency `m`. Positive frequencies
+/// multiply cos(mθ) and negative ones multiply sin(|m|θ).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZernikeTerm {
+    pub n: u32,
+    pub m: i32,
+    pub normalization: f32,
+}
+
+impl ZernikeTerm {
+    /// The `j`-th fringe term, starting at 1.
+    pub fn fringe(j: usize) -> Option<Self> {
+        let max_terms = ZernikeOrdering::Fringe.max_terms().unwrap_or(usize::MAX);
+
+        if j == 0 || j > max_terms {
+            return None;
+        }
+
+        // The last fringe term is the 12th order spherical aberration, which breaks the pattern
+        if j == max_terms {
+            return Some(Self {
+                n: 12,
+                m: 0,
+                normalization: 1.0,
+            });
+        }
+
+        // Terms are grouped by (n + |m|) / 2, starting from the highest frequency, with cosine
+        // before sine
+        let group = (j - 1).isqrt();
+        let position = j - 1 - group * group;
+        let frequency = group - position / 2;
+
+        let m = if position.is_multiple_of(2) {
+            frequency as i32
+        } else {
+            -(frequency as i32)
+        };
+
+        Some(Self {
+            n: (2 * group - frequency) as u32,
+            m,
+            normalization: 1.0,
+        })
+    }
+
+    /// The `j`-th Noll term, starting at 1.
+    pub fn standard(j: usize) -> Option<Self> {
+        if j == 0 {
+            return None;
+        }
+
+        let mut n = 0;
+        let mut remainder = j - 1;
+
+        while remainder > n {
+            n += 1;
+            remainder -= n;
+        }
+
+        // Even indices are cosine terms and odd indices are sine terms
+        let frequency = (n % 2 + 2 * ((remainder + (n + 1) % 2) / 2)) as i32;
+        let m = if j.is_multiple_of(2) {
+            frequency
+        } else {
+            -frequency
+        };
+
+        let normalization = if m == 0 {
+            ((n + 1) as f32).sqrt()
+        } else {
+            (2.0 * (n + 1) as f32).sqrt()
+        };
+
+        Some(Self {
+            n: n as u32,
+            m,
+            normalization,
+        })
+    }
+
+    /// The radial polynomial is written as ρ^|m| P(ρ²). Returns P and its derivative.
+    fn radial(&self, rho2: f32) -> (f32, f32) {
+        let frequency = self.m.unsigned_abs();
+        let a = (self.n + frequency) / 2;
+        let b = (self.n - frequency) / 2;
+
+        // The coefficient of ρ^(n - 2s) is (-1)^s (n - s)! / (s! (a - s)! (b - s)!), starting
+        // from the binomial n! / (a! b!)
+        let mut coefficient = (0..b).fold(1.0, |binomial, i| {
+            binomial * (self.n - i) as f32 / (i + 1) as f32
+        });
+
+        let mut value = 0.0;
+        let mut derivative = 0.0;
+
+        for s in 0..=b {
+            let power = (b - s) as i32;
+
+            value += coefficient * rho2.powi(power);
+
+            if power > 0 {
+                derivative += coefficient * power as f32 * rho2.powi(power - 1);
+            }
+
+            if s < b {
+                coefficient *=
+                    -((a - s) as f32 * (b - s) as f32) / ((s + 1) as f32 * (self.n - s) as f32);
+            }
+        }
+
+        (value, derivative)
+    }
+
+    /// Value and gradient of the polynomial at `point`, in coordinates normalized to the unit
+    /// circle.
+    pub fn value_and_gradient(&self, point: Vec2) -> (f32, Vec2) {
+        let frequency = self.m.unsigned_abs();
+        let (radial, radial_derivative) = self.radial(point.length_squared());
+
+        // ρ^|m| cos(mθ) and ρ^|m| sin(mθ) are the real and imaginary parts of (x + iy)^|m|,
+        // which avoids the singularity of polar coordinates at the origin
+        let mut power = Vec2::X;
+        let mut previous = Vec2::ZERO;
+
+        for _ in 0..frequency {
+            previous = power;
+            power = power.rotate(point);
+        }
+
+        let derivative = frequency as f32 * previous;
+
+        let (angular, angular_gradient) = if self.m >= 0 {
+            (power.x, Vec2::new(derivative.x, -derivative.y))
+        } else {
+            (power.y, Vec2::new(derivative.y, derivative.x))
+        };
+
+        let value = radial * angular;
+        let gradient = 2.0 * radial_derivative * angular * point + radial * angular_gradient;
+
+        (self.normalization * value, self.normalization * gradient)
+    }
+
+    /// Second derivative of the polynomial along `meridian` at the origin.
+    pub fn paraxial_curvature(&self, meridian: Meridian) -> f32 {
+        let (radial, radial_derivative) = self.radial(0.0);
+
+        let curvature = match (self.m, meridian) {
+            (0, _) => 2.0 * radial_derivative,
+            (2, Meridian::X) => 2.0 * radial,
+            (2, Meridian::Y) => -2.0 * radial,
+            _ => 0.0,
+        };
+
+        self.normalization * curvature
+    }
+}
+
+/// Least-squares fit of Zernike polynomials to samples over the unit circle.
+#[derive(Debug, Clone)]
+pub struct ZernikeFit {
+    pub ordering: ZernikeOrdering,
+    /// Coefficient of each term of the ordering, starting at term 1, in the units of the samples.
+    pub coefficients: Vec<f32>,
+    /// RMS of the difference between the samples and the fitted polynomials.
+    pub residual_rms: f32,
+    /// Difference between the largest and smallest residuals.
+    pub residual_peak_to_valley: f32,
+}
+
+impl ZernikeFit {
+    /// Fits the first `terms` terms of `ordering` to `samples`, made of points normalized to the
+    /// unit circle and their values. The number of terms is limited to the ones defined by the
+    /// ordering. Returns `None` when the samples are not enough to tell the terms apart.
+    pub fn new(
+        ordering: ZernikeOrdering,
+        terms: usize,
+        samples: impl IntoIterator<Item = (Vec2, f32)>,
+    ) -> Option<Self> {
+        let terms: Vec<_> = (1..=terms.min(ordering.max_terms().unwrap_or(usize::MAX)))
+            .map_while(|j| ordering.term(j))
+            .collect();
+        let samples: Vec<_> = samples.into_iter().collect();
+        let size = terms.len();
+
+        if size == 0 || samples.len() < size {
+            return None;
+        }
+
+        // Normal equations AᵀA c = Aᵀw, where each row of A holds the terms at a sample
+        let mut matrix = vec![0.0; size * size];
+        let mut rhs = vec![0.0; size];
+        let mut row = vec![0.0; size];
+
+        for &(point, value) in &samples {
+            for (value, term) in row.iter_mut().zip(&terms) {
+                *value = term.value_and_gradient(point).0 as f64;
+            }
+
+            for i in 0..size {
+                rhs[i] += row[i] * value as f64;
+
+                for k in 0..size {
+                    matrix[i * size + k] += row[i] * row[k];
+                }
+            }
+        }
+
+        let mut fit = Self {
+            ordering,
+            coefficients: solve_linear(matrix, rhs)?
+                .into_iter()
+                .map(|coefficient| coefficient as f32)
+                .collect(),
+            residual_rms: 0.0,
+            residual_peak_to_valley: 0.0,
+        };
+
+        let residuals: Vec<_> = samples
+            .iter()
+            .map(|&(point, value)| value - fit.value(point))
+            .collect();
+        let (min, max) = residuals.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(min, max), &residual| (min.min(residual), max.max(residual)),
+        );
+
+        fit.residual_rms = (residuals
+            .iter()
+            .map(|residual| residual * residual)
+            .sum::<f32>()
+            / residuals.len() as f32)
+            .sqrt();
+        fit.residual_peak_to_valley = max - min;
+
+        Some(fit)
+    }
+
+    /// Terms of the fit along with their coefficients.
+    pub fn terms(&self) -> impl Iterator<Item = (ZernikeTerm, f32)> {
+        self.coefficients
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &coefficient)| Some((self.ordering.term(i + 1)?, coefficient)))
+    }
+
+    /// Value of the fitted polynomials at `point`, normalized to the unit circle.
+    pub fn value(&self, point: Vec2) -> f32 {
+        self.terms()
+            .map(|(term, coefficient)| coefficient * term.value_and_gradient(point).0)
+            .sum()
+    }
+}
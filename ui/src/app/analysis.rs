@@ -0,0 +1,100 @@
+use std::sync::{
+    Arc,
+    mpsc::{self, Receiver, TryRecvError},
+};
+
+use egui::Context;
+use optics::system::System;
+
+use crate::app::State;
+
+/// Result of an analysis that is too slow to run on every frame.
+///
+/// The analysis runs on a worker thread whenever its settings or the system change, and on every
+/// Sync & Run. Only one run is in flight at a time, so changes made while it is running are picked
+/// up once it finishes.
+pub struct Analysis<S, T> {
+    /// Settings, system and revision of the last run that was started.
+    inputs: Option<(S, Arc<System>, u64)>,
+    result: Option<T>,
+    worker: Option<Receiver<T>>,
+}
+
+impl<S, T> Analysis<S, T>
+where
+    S: Clone + PartialEq + Send + 'static,
+    T: Send + 'static,
+{
+    pub const fn new() -> Self {
+        Self {
+            inputs: None,
+            result: None,
+            worker: None,
+        }
+    }
+
+    /// Collects the result of the last run and starts `run` again if its inputs changed since.
+    /// Returns whether a new result arrived, so that anything derived from it can be rebuilt.
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        state: &State,
+        settings: &S,
+        run: fn(&System, &S) -> T,
+    ) -> bool {
+        let mut updated = false;
+
+        if let Some(worker) = &self.worker {
+            match worker.try_recv() {
+                Ok(result) => {
+                    self.result = Some(result);
+                    updated = true;
+                }
+                Err(TryRecvError::Empty) => return false,
+                // The run panicked, there is no point in repeating it with the same inputs
+                Err(TryRecvError::Disconnected) => {}
+            }
+
+            self.worker = None;
+        }
+
+        let changed = self
+            .inputs
+            .as_ref()
+            .is_none_or(|(last_settings, system, revision)| {
+                last_settings != settings || **system != state.system || *revision != state.revision
+            });
+
+        if changed {
+            let system = Arc::new(state.system.clone());
+            let (sender, receiver) = mpsc::channel();
+
+            std::thread::spawn({
+                let ctx = ctx.clone();
+                let settings = settings.clone();
+                let system = system.clone();
+
+                move || {
+                    // The tab may have been closed in the meantime
+                    let _ = sender.send(run(&system, &settings));
+                    ctx.request_repaint();
+                }
+            });
+
+            self.inputs = Some((settings.clone(), system, state.revision));
+            self.worker = Some(receiver);
+        }
+
+        updated
+    }
+
+    /// Result of the last run that finished, if any.
+    pub const fn result(&self) -> Option<&T> {
+        self.result.as_ref()
+    }
+
+    /// Whether a run is in progress, in which case `result` may be outdated.
+    pub const fn is_running(&self) -> bool {
+        self.worker.is_some()
+    }
+}
@@ -1,3 +1,4 @@
+mod analysis;
 mod formatting;
 mod log;
 pub mod si;
@@ -48,24 +49,17 @@ impl App {
     }
 
     pub fn sync_and_run(&mut self) {
+        // Analyses shown in other tabs run again
+        self.state.revision += 1;
+
         let thickness = self.state.system.thickness();
-        let power = self
-            .state
-            .system
-            .power(self.state.system.wavelengths[0])
-            .map_or_else(
-                || "-".to_owned(),
-                |power| format!("{}⁻¹", self.state.formatting.length(power)),
-            );
 
+        // First-order properties and chromatic power have their own tab
         self.clear_logs();
         self.log(
             LogLevel::Info,
             "Measurements",
-            format!(
-                "Thickness: {}\nPower: {power}",
-                self.state.formatting.length(thickness),
-            ),
+            format!("Thickness: {}", self.state.formatting.length(thickness)),
         );
     }
 }
@@ -95,9 +89,49 @@ impl eframe::App for App {
                         self.open(TabKind::new_system_2d_viewer());
                     }
 
+                    if ui.button("Ray Fans").clicked() {
+                        self.open(TabKind::new_ray_fans());
+                    }
+
                     if ui.button("Config").clicked() {
                         self.open(TabKind::new_config());
                     }
+
+                    if ui.button("Fields").clicked() {
+                        self.open(TabKind::new_fields());
+                    }
+
+                    if ui.button("Wavelengths").clicked() {
+                        self.open(TabKind::new_wavelengths());
+                    }
+
+                    if ui.button("First Order").clicked() {
+                        self.open(TabKind::new_first_order());
+                    }
+
+                    if ui.button("Seidel").clicked() {
+                        self.open(TabKind::new_seidel());
+                    }
+
+                    if ui.button("Spot Diagram").clicked() {
+                        self.open(TabKind::new_spot_diagram());
+                    }
+
+                    if ui.button("Wavefront").clicked() {
+                        self.open(TabKind::new_wavefront());
+                    }
+
+                    if ui.button("Zernike").clicked() {
+                        self.open(TabKind::new_zernike());
+                    }
+
+                    if ui.button("FFT PSF & MTF").clicked() {
+                        self.open(TabKind::new_fft_psf());
+                    }
+
+                    if ui.button("Huygens PSF").clicked() {
+                        self.open(TabKind::new_huygens_psf());
+                    }
                 });
             });
         });
@@ -7,6 +7,8 @@ pub struct State {
     pub(crate) system: System,
     pub(crate) log: Vec<Log>,
     pub(crate) formatting: Formatting,
+    /// Number of times the system was synced, so that analyses run again when it changes.
+    pub(crate) revision: u64,
 }
 
 impl Default for State {
@@ -15,6 +17,7 @@ impl Default for State {
             system: System::default(),
             log: Vec::new(),
             formatting: Formatting::default(),
+            revision: 0,
         }
     }
 }
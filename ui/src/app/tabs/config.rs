@@ -1,3 +1,6 @@
+use egui::ComboBox;
+use optics::pupil::RayAiming;
+
 use crate::app::{State, widgets};
 
 pub struct Config;
@@ -13,8 +16,14 @@ impl Config {
 
         ui.separator();
 
-        ui.label("Reference wavelength:");
-        widgets::wavelength(ui, &mut state.system.wavelengths[0], &state.formatting);
+        ui.label("Ray aiming:");
+        ComboBox::from_id_salt("ray_aiming")
+            .selected_text(state.system.ray_aiming.name())
+            .show_ui(ui, |ui| {
+                for aiming in RayAiming::ALL {
+                    ui.selectable_value(&mut state.system.ray_aiming, aiming, aiming.name());
+                }
+            });
 
         ui.separator();
 
@@ -0,0 +1,149 @@
+use egui::{Color32, DragValue, TextureHandle, Ui};
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints};
+use optics::{
+    diffraction::{Mtf, Psf},
+    system::System,
+};
+
+use crate::app::{
+    State,
+    analysis::Analysis,
+    widgets::{psf_image, psf_plot, update_texture, wavelength_selector},
+};
+
+/// Side of each plot.
+const PLOT_SIZE: f32 = 300.0;
+
+/// Number of Airy radii shown around the center of each PSF.
+const PSF_EXTENT: f32 = 4.0;
+
+/// Wavelength and pupil sampling.
+type Settings = (usize, usize);
+
+pub struct FftPsf {
+    wavelength: usize,
+    sampling: usize,
+    /// PSF and MTF of every field point.
+    analysis: Analysis<Settings, Vec<Option<(Psf, Mtf)>>>,
+    textures: Vec<Option<TextureHandle>>,
+}
+
+impl FftPsf {
+    pub fn new() -> Self {
+        Self {
+            wavelength: 0,
+            sampling: 64,
+            analysis: Analysis::new(),
+            textures: Vec::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &state.system;
+
+        ui.horizontal(|ui| {
+            wavelength_selector(ui, "fft_psf_wavelength", &mut self.wavelength, system);
+
+            ui.separator();
+
+            ui.label("Pupil sampling:");
+            ui.add(DragValue::new(&mut self.sampling).range(8..=256));
+        });
+
+        ui.separator();
+
+        let Some(wavelength) = system.wavelengths.get(self.wavelength) else {
+            ui.label("The system has no wavelengths.");
+            return;
+        };
+
+        let extent = system
+            .airy_radius(wavelength.value)
+            .map_or(0.01, |radius| PSF_EXTENT * radius);
+
+        let settings = (self.wavelength, self.sampling);
+
+        if self.analysis.update(ui.ctx(), state, &settings, run) {
+            let fields = self.analysis.result().map_or(&[][..], Vec::as_slice);
+
+            self.textures.resize_with(fields.len(), || None);
+
+            for (texture, result) in self.textures.iter_mut().zip(fields) {
+                match result {
+                    Some((psf, _)) => {
+                        update_texture(ui.ctx(), texture, "psf", psf_image(psf));
+                    }
+                    None => *texture = None,
+                }
+            }
+        }
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some(fields) = self.analysis.result() else {
+            return;
+        };
+
+        for (i, (result, texture)) in fields.iter().zip(&self.textures).enumerate() {
+            ui.strong(format!("Field {}", i + 1));
+
+            let (Some((psf, mtf)), Some(texture)) = (result, texture) else {
+                ui.label("The field point could not be traced.");
+                continue;
+            };
+
+            ui.horizontal(|ui| {
+                psf_plot(ui, ("fft_psf", i), texture.id(), psf, extent, PLOT_SIZE);
+                mtf_plot(ui, ("fft_mtf", i), mtf);
+            });
+
+            ui.separator();
+        }
+    }
+}
+
+fn run(system: &System, &(wavelength, sampling): &Settings) -> Vec<Option<(Psf, Mtf)>> {
+    let Some(wavelength) = system.wavelengths.get(wavelength) else {
+        return Vec::new();
+    };
+
+    system
+        .fields
+        .iter()
+        .map(|point| {
+            Some((
+                system.fft_psf(point, wavelength.value, sampling)?,
+                system.fft_mtf(point, sampling)?,
+            ))
+        })
+        .collect()
+}
+
+fn mtf_plot(ui: &mut Ui, id: (&str, usize), mtf: &Mtf) {
+    let line = |values: &[f32]| -> PlotPoints {
+        mtf.frequencies
+            .iter()
+            .zip(values)
+            .map(|(&frequency, &value)| [frequency as f64, value as f64])
+            .collect()
+    };
+
+    Plot::new(id)
+        .legend(Legend::default())
+        .x_axis_label("Frequency (cycles/mm)")
+        .y_axis_label("MTF")
+        .include_x(0.0)
+        .include_y(0.0)
+        .include_y(1.0)
+        .width(PLOT_SIZE * 1.5)
+        .height(PLOT_SIZE)
+        .show(ui, |ui| {
+            ui.line(Line::new("Tangential", line(&mtf.tangential)));
+            ui.line(Line::new("Sagittal", line(&mtf.sagittal)).style(LineStyle::dashed_dense()));
+            ui.line(
+                Line::new("Diffraction limit", line(&mtf.diffraction_limit)).color(Color32::GRAY),
+            );
+        });
+}
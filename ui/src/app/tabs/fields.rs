@@ -0,0 +1,99 @@
+use egui::{Button, ComboBox, DragValue, Grid, Ui};
+use optics::{
+    fields::{FieldPoint, FieldType},
+    glam::Vec2,
+};
+
+use crate::app::{
+    State,
+    formatting::Formatting,
+    widgets::{angle_value, length_value},
+};
+
+pub struct Fields;
+
+impl Fields {
+    pub fn new() -> Self {
+        Fields
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &mut state.system;
+        let fmt = &state.formatting;
+
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+
+            ComboBox::from_id_salt("field_type")
+                .selected_text(system.field_type.name())
+                .show_ui(ui, |ui| {
+                    for field_type in FieldType::ALL {
+                        ui.selectable_value(&mut system.field_type, field_type, field_type.name());
+                    }
+                });
+
+            if ui.button("Add field").clicked() {
+                let value = system.fields.last().map_or(Vec2::ZERO, |point| point.value);
+                system.fields.push(FieldPoint::new(value));
+            }
+        });
+
+        ui.separator();
+
+        let field_type = system.field_type;
+        let mut removed = None;
+
+        Grid::new("fields")
+            .striped(true)
+            .num_columns(10)
+            .show(ui, |ui| {
+                for name in [
+                    "#", "X", "Y", "Weight", "VDX", "VDY", "VCX", "VCY", "VAN", "",
+                ] {
+                    ui.strong(name);
+                }
+
+                ui.end_row();
+
+                for (i, point) in system.fields.iter_mut().enumerate() {
+                    ui.label((i + 1).to_string());
+                    value(ui, &mut point.value.x, field_type, fmt);
+                    value(ui, &mut point.value.y, field_type, fmt);
+                    ui.add(
+                        DragValue::new(&mut point.weight)
+                            .speed(0.01)
+                            .range(0.0..=f32::MAX),
+                    );
+                    factor(ui, &mut point.vignetting.decenter.x);
+                    factor(ui, &mut point.vignetting.decenter.y);
+                    factor(ui, &mut point.vignetting.compression.x);
+                    factor(ui, &mut point.vignetting.compression.y);
+                    angle_value(ui, &mut point.vignetting.angle, fmt);
+
+                    if ui.add(Button::new("Remove")).clicked() {
+                        removed = Some(i);
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = removed {
+            system.fields.remove(i);
+        }
+    }
+}
+
+/// Coordinate of a field point, in the units of its type.
+fn value(ui: &mut Ui, value: &mut f32, field_type: FieldType, fmt: &Formatting) {
+    if field_type.is_angle() {
+        angle_value(ui, value, fmt);
+    } else {
+        length_value(ui, value, fmt);
+    }
+}
+
+/// Vignetting factor, relative to the pupil semi-diameter.
+fn factor(ui: &mut Ui, value: &mut f32) {
+    ui.add(DragValue::new(value).speed(0.01).fixed_decimals(3));
+}
@@ -0,0 +1,172 @@
+use egui::{Grid, Ui};
+use optics::{
+    first_order::FirstOrder as Properties, pupil::Pupil, ray::Wavelength, system::System,
+};
+
+use crate::app::{State, analysis::Analysis};
+
+/// Power and diffraction efficiency of the system at one of its wavelengths.
+struct Chromatic {
+    wavelength: Wavelength,
+    power: Option<f32>,
+    efficiency: Option<f32>,
+}
+
+pub struct FirstOrder {
+    analysis: Analysis<(), (Option<Properties>, Vec<Chromatic>)>,
+}
+
+impl FirstOrder {
+    pub fn new() -> Self {
+        Self {
+            analysis: Analysis::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let wavelength = state.system.primary_wavelength();
+
+        ui.label(format!("Primary wavelength: {wavelength:.4} µm"));
+        ui.separator();
+
+        self.analysis.update(ui.ctx(), state, &(), run);
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some((first_order, chromatic)) = self.analysis.result() else {
+            return;
+        };
+
+        let fmt = &state.formatting;
+
+        let Some(first_order) = first_order else {
+            ui.label("The system has surfaces that are not supported by paraxial calculations.");
+            return;
+        };
+
+        let unitless = |value: f32| format!("{value:.*}", fmt.decimal_places);
+        let pupil = |pupil: Pupil| {
+            format!(
+                "⌀{} at {}",
+                fmt.length(2.0 * pupil.semi_diameter),
+                fmt.length(pupil.position),
+            )
+        };
+
+        let rows = [
+            (
+                "Effective focal length",
+                fmt.length(first_order.effective_focal_length),
+            ),
+            (
+                "Front focal length",
+                fmt.length(first_order.front_focal_length),
+            ),
+            (
+                "Back focal length",
+                fmt.length(first_order.back_focal_length),
+            ),
+            (
+                "Front principal plane",
+                fmt.length(first_order.principal_planes.0),
+            ),
+            (
+                "Back principal plane",
+                fmt.length(first_order.principal_planes.1),
+            ),
+            ("Front nodal plane", fmt.length(first_order.nodal_planes.0)),
+            ("Back nodal plane", fmt.length(first_order.nodal_planes.1)),
+            ("Working F/#", unitless(first_order.working_f_number)),
+            (
+                "Image space NA",
+                unitless(first_order.image_numerical_aperture),
+            ),
+            (
+                "Paraxial magnification",
+                unitless(first_order.magnification),
+            ),
+            (
+                "Paraxial image height",
+                fmt.length(first_order.image_height),
+            ),
+            (
+                "Lagrange invariant",
+                unitless(first_order.lagrange_invariant),
+            ),
+            ("Entrance pupil", pupil(first_order.entrance_pupil)),
+            ("Exit pupil", pupil(first_order.exit_pupil)),
+        ];
+
+        Grid::new("first_order")
+            .striped(true)
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (name, value) in rows {
+                    ui.strong(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        ui.label(
+            "Object space positions are measured from the first surface and image space \
+             positions from the last surface, except for the exit pupil, which is measured from \
+             the image surface.",
+        );
+
+        // Power varies with wavelength in dispersive systems
+        ui.separator();
+        ui.strong("Chromatic power");
+
+        let diffractive = chromatic.iter().any(|row| row.efficiency.is_some());
+
+        Grid::new("chromatic_power")
+            .striped(true)
+            .num_columns(if diffractive { 3 } else { 2 })
+            .show(ui, |ui| {
+                ui.strong("Wavelength");
+                ui.strong("Power");
+
+                if diffractive {
+                    ui.strong("Diffraction efficiency");
+                }
+
+                ui.end_row();
+
+                for row in chromatic {
+                    ui.label(format!("{:.4} µm", row.wavelength));
+                    ui.label(row.power.map_or_else(
+                        || "-".to_owned(),
+                        |power| format!("{}⁻¹", fmt.length(power)),
+                    ));
+
+                    if diffractive {
+                        ui.label(row.efficiency.map_or_else(
+                            || "-".to_owned(),
+                            |efficiency| format!("{:.*} %", fmt.decimal_places, 100.0 * efficiency),
+                        ));
+                    }
+
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn run(system: &System, _: &()) -> (Option<Properties>, Vec<Chromatic>) {
+    let first_order = system.first_order(system.primary_wavelength());
+    let chromatic = system
+        .wavelengths
+        .iter()
+        .map(|wavelength| Chromatic {
+            wavelength: wavelength.value,
+            power: system.power(wavelength.value),
+            efficiency: system.diffraction_efficiency(wavelength.value),
+        })
+        .collect();
+
+    (first_order, chromatic)
+}
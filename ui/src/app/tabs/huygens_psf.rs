@@ -0,0 +1,133 @@
+use egui::{DragValue, TextureHandle, Ui};
+use optics::{diffraction::Psf, system::System};
+
+use crate::app::{
+    State,
+    analysis::Analysis,
+    widgets::{field_selector, psf_image, psf_plot, update_texture, wavelength_selector},
+};
+
+/// Side of the PSF plot.
+const PLOT_SIZE: f32 = 400.0;
+
+/// Field, wavelength, pupil sampling, image sampling and image spacing.
+type Settings = (usize, usize, usize, usize, f32);
+
+pub struct HuygensPsf {
+    field: usize,
+    wavelength: usize,
+    pupil_sampling: usize,
+    size: usize,
+    /// Distance between samples of the image, in micrometers.
+    spacing: f32,
+    analysis: Analysis<Settings, Option<Psf>>,
+    texture: Option<TextureHandle>,
+}
+
+impl HuygensPsf {
+    pub fn new() -> Self {
+        Self {
+            field: 0,
+            wavelength: 0,
+            pupil_sampling: 32,
+            size: 32,
+            spacing: 1.0,
+            analysis: Analysis::new(),
+            texture: None,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &state.system;
+
+        ui.horizontal(|ui| {
+            field_selector(ui, "huygens_psf_field", &mut self.field, system);
+
+            ui.separator();
+
+            wavelength_selector(ui, "huygens_psf_wavelength", &mut self.wavelength, system);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Pupil sampling:");
+            ui.add(DragValue::new(&mut self.pupil_sampling).range(4..=256));
+
+            ui.separator();
+
+            ui.label("Image sampling:");
+            ui.add(DragValue::new(&mut self.size).range(4..=256));
+
+            ui.separator();
+
+            ui.label("Image spacing:");
+            ui.add(
+                DragValue::new(&mut self.spacing)
+                    .suffix(" µm")
+                    .speed(0.01)
+                    .range(0.001..=1000.0),
+            );
+        });
+
+        ui.separator();
+
+        if system.fields.get(self.field).is_none()
+            || system.wavelengths.get(self.wavelength).is_none()
+        {
+            ui.label("The system has no field points or wavelengths.");
+            return;
+        }
+
+        let settings = (
+            self.field,
+            self.wavelength,
+            self.pupil_sampling,
+            self.size,
+            self.spacing,
+        );
+
+        if self.analysis.update(ui.ctx(), state, &settings, run) {
+            match self.analysis.result() {
+                Some(Some(psf)) => {
+                    update_texture(ui.ctx(), &mut self.texture, "psf", psf_image(psf));
+                }
+                _ => self.texture = None,
+            }
+        }
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some(result) = self.analysis.result() else {
+            return;
+        };
+
+        let (Some(psf), Some(texture)) = (result, &self.texture) else {
+            ui.label("The field point could not be traced, or the image space is telecentric.");
+            return;
+        };
+
+        let decimal_places = state.formatting.decimal_places;
+        ui.label(format!("Peak: {:.decimal_places$}", psf.peak));
+
+        psf_plot(
+            ui,
+            "huygens_psf",
+            texture.id(),
+            psf,
+            psf.spacing * psf.size as f32 / 2.0,
+            PLOT_SIZE,
+        );
+    }
+}
+
+fn run(
+    system: &System,
+    &(field, wavelength, pupil_sampling, size, spacing): &Settings,
+) -> Option<Psf> {
+    let point = system.fields.get(field)?;
+    let wavelength = system.wavelengths.get(wavelength)?.value;
+
+    // Lengths are in millimeters
+    system.huygens_psf(point, wavelength, pupil_sampling, size, spacing * 1e-3)
+}
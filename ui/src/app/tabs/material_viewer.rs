@@ -22,6 +22,17 @@ impl MaterialViewer {
                 state.system.materials.len(),
                 |i| state.system.materials[i].name(),
             );
+
+            // GRIN materials are plotted on the vertex, so their profile is listed separately
+            if let Some(gradient) = state.system.materials[self.material].gradient() {
+                let [r2, r4, r6] = gradient.radial;
+                let [z1, z2, z3] = gradient.axial;
+
+                ui.separator();
+                ui.label(format!(
+                    "GRIN: n = n₀ {r2:+e} r² {r4:+e} r⁴ {r6:+e} r⁶ {z1:+e} z {z2:+e} z² {z3:+e} z³"
+                ));
+            }
         });
 
         ui.separator();
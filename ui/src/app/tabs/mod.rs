@@ -1,16 +1,36 @@
 pub use config::*;
+pub use fft_psf::*;
+pub use fields::*;
+pub use first_order::*;
+pub use huygens_psf::*;
 pub use log::*;
 pub use material_viewer::*;
+pub use ray_fans::*;
+pub use seidel::*;
+pub use spot_diagram::*;
 pub use surface_editor::*;
 pub use system_2d_viewer::*;
+pub use wavefront::*;
+pub use wavelengths::*;
+pub use zernike::*;
 
 use super::State;
 
 mod config;
+mod fft_psf;
+mod fields;
+mod first_order;
+mod huygens_psf;
 mod log;
 mod material_viewer;
+mod ray_fans;
+mod seidel;
+mod spot_diagram;
 mod surface_editor;
 mod system_2d_viewer;
+mod wavefront;
+mod wavelengths;
+mod zernike;
 
 #[non_exhaustive]
 pub enum TabKind {
@@ -19,6 +39,16 @@ pub enum TabKind {
     MaterialViewer(MaterialViewer),
     System2dViewer(System2dViewer),
     Config(Config),
+    Fields(Fields),
+    Wavelengths(Wavelengths),
+    FirstOrder(FirstOrder),
+    Seidel(Seidel),
+    SpotDiagram(SpotDiagram),
+    RayFans(RayFans),
+    Wavefront(Wavefront),
+    Zernike(Zernike),
+    FftPsf(FftPsf),
+    HuygensPsf(HuygensPsf),
 }
 
 pub struct Tab {
@@ -46,6 +76,16 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
             TabKind::MaterialViewer(_) => "Material Viewer".into(),
             TabKind::System2dViewer(_) => "System 2D Viewer".into(),
             TabKind::Config(_) => "Config".into(),
+            TabKind::Fields(_) => "Fields".into(),
+            TabKind::Wavelengths(_) => "Wavelengths".into(),
+            TabKind::FirstOrder(_) => "First Order".into(),
+            TabKind::Seidel(_) => "Seidel".into(),
+            TabKind::SpotDiagram(_) => "Spot Diagram".into(),
+            TabKind::RayFans(_) => "Ray Fans".into(),
+            TabKind::Wavefront(_) => "Wavefront".into(),
+            TabKind::Zernike(_) => "Zernike".into(),
+            TabKind::FftPsf(_) => "FFT PSF & MTF".into(),
+            TabKind::HuygensPsf(_) => "Huygens PSF".into(),
         }
     }
 
@@ -58,6 +98,16 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                 TabKind::MaterialViewer(viewer) => viewer.ui(ui, self.state),
                 TabKind::System2dViewer(viewer) => viewer.ui(ui, self.state),
                 TabKind::Config(config) => config.ui(ui, self.state),
+                TabKind::Fields(fields) => fields.ui(ui, self.state),
+                TabKind::Wavelengths(wavelengths) => wavelengths.ui(ui, self.state),
+                TabKind::FirstOrder(first_order) => first_order.ui(ui, self.state),
+                TabKind::Seidel(seidel) => seidel.ui(ui, self.state),
+                TabKind::SpotDiagram(diagram) => diagram.ui(ui, self.state),
+                TabKind::RayFans(fans) => fans.ui(ui, self.state),
+                TabKind::Wavefront(wavefront) => wavefront.ui(ui, self.state),
+                TabKind::Zernike(zernike) => zernike.ui(ui, self.state),
+                TabKind::FftPsf(psf) => psf.ui(ui, self.state),
+                TabKind::HuygensPsf(psf) => psf.ui(ui, self.state),
             });
     }
 
@@ -92,4 +142,44 @@ impl TabKind {
     pub fn new_config() -> Self {
         TabKind::Config(Config::new())
     }
+
+    pub fn new_fields() -> Self {
+        TabKind::Fields(Fields::new())
+    }
+
+    pub fn new_wavelengths() -> Self {
+        TabKind::Wavelengths(Wavelengths::new())
+    }
+
+    pub fn new_first_order() -> Self {
+        TabKind::FirstOrder(FirstOrder::new())
+    }
+
+    pub fn new_seidel() -> Self {
+        TabKind::Seidel(Seidel::new())
+    }
+
+    pub fn new_spot_diagram() -> Self {
+        TabKind::SpotDiagram(SpotDiagram::new())
+    }
+
+    pub fn new_ray_fans() -> Self {
+        TabKind::RayFans(RayFans::new())
+    }
+
+    pub fn new_wavefront() -> Self {
+        TabKind::Wavefront(Wavefront::new())
+    }
+
+    pub fn new_zernike() -> Self {
+        TabKind::Zernike(Zernike::new())
+    }
+
+    pub fn new_fft_psf() -> Self {
+        TabKind::FftPsf(FftPsf::new())
+    }
+
+    pub fn new_huygens_psf() -> Self {
+        TabKind::HuygensPsf(HuygensPsf::new())
+    }
 }
@@ -0,0 +1,74 @@
+use egui::{DragValue, Ui};
+use optics::{fans::RayFan, system::System};
+
+use crate::app::{State, analysis::Analysis, widgets::fan_plot};
+
+pub struct RayFans {
+    samples: usize,
+    /// Fans of each field point.
+    analysis: Analysis<usize, Vec<Option<Vec<RayFan>>>>,
+}
+
+impl RayFans {
+    pub fn new() -> Self {
+        Self {
+            samples: 41,
+            analysis: Analysis::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            ui.label("Rays per fan:");
+            ui.add(DragValue::new(&mut self.samples).range(3..=501));
+        });
+
+        ui.separator();
+
+        self.analysis.update(ui.ctx(), state, &self.samples, run);
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some(fans) = self.analysis.result() else {
+            return;
+        };
+
+        for (i, fans) in fans.iter().enumerate() {
+            ui.strong(format!("Field {}", i + 1));
+
+            let Some(fans) = fans else {
+                ui.label("The field point could not be traced.");
+                continue;
+            };
+
+            ui.horizontal(|ui| {
+                fan_plot(
+                    ui,
+                    ("tangential_fan", i),
+                    "Py",
+                    "ey (mm)",
+                    fans.iter().map(|fan| (fan.wavelength, &fan.tangential[..])),
+                );
+                fan_plot(
+                    ui,
+                    ("sagittal_fan", i),
+                    "Px",
+                    "ex (mm)",
+                    fans.iter().map(|fan| (fan.wavelength, &fan.sagittal[..])),
+                );
+            });
+
+            ui.separator();
+        }
+    }
+}
+
+fn run(system: &System, &samples: &usize) -> Vec<Option<Vec<RayFan>>> {
+    system
+        .fields
+        .iter()
+        .map(|point| system.ray_fans(point, samples))
+        .collect()
+}
@@ -0,0 +1,105 @@
+use egui::{Grid, Ui};
+use egui_plot::{Bar, BarChart, Legend, Plot};
+use optics::{
+    seidel::{self, SeidelCoefficients},
+    system::System,
+};
+
+use crate::app::{State, analysis::Analysis};
+
+/// Width of each bar of the chart, so that the bars of a surface fill most of the space between
+/// surfaces.
+const BAR_WIDTH: f64 = 0.8 / SeidelCoefficients::NAMES.len() as f64;
+
+pub struct Seidel {
+    analysis: Analysis<(), Option<seidel::Seidel>>,
+}
+
+impl Seidel {
+    pub fn new() -> Self {
+        Self {
+            analysis: Analysis::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        self.analysis.update(ui.ctx(), state, &(), run);
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some(result) = self.analysis.result() else {
+            return;
+        };
+
+        let Some(seidel) = result else {
+            ui.label("The system has surfaces that are not supported by paraxial calculations.");
+            return;
+        };
+
+        let decimal_places = state.formatting.decimal_places;
+
+        Grid::new("seidel")
+            .striped(true)
+            .num_columns(SeidelCoefficients::NAMES.len() + 1)
+            .show(ui, |ui| {
+                ui.strong("Surface");
+
+                for name in SeidelCoefficients::NAMES {
+                    ui.strong(name);
+                }
+
+                ui.end_row();
+
+                for (index, coefficients) in &seidel.surfaces {
+                    ui.label(index.to_string());
+
+                    for value in coefficients.to_array() {
+                        ui.label(format!("{value:+.*}", decimal_places));
+                    }
+
+                    ui.end_row();
+                }
+
+                ui.strong("Total");
+
+                for value in seidel.total.to_array() {
+                    ui.strong(format!("{value:+.*}", decimal_places));
+                }
+
+                ui.end_row();
+            });
+
+        ui.separator();
+
+        let available_size = ui.available_size();
+
+        Plot::new("seidel_chart")
+            .legend(Legend::default())
+            .x_axis_label("Surface")
+            .height(available_size.y.max(320.0))
+            .show(ui, |ui| {
+                for (i, name) in SeidelCoefficients::NAMES.into_iter().enumerate() {
+                    // Bars of each surface are grouped around its index
+                    let offset =
+                        (i as f64 - 0.5 * (SeidelCoefficients::NAMES.len() - 1) as f64) * BAR_WIDTH;
+
+                    let bars = seidel
+                        .surfaces
+                        .iter()
+                        .map(|(index, coefficients)| {
+                            Bar::new(*index as f64 + offset, coefficients.to_array()[i] as f64)
+                                .width(BAR_WIDTH)
+                        })
+                        .collect();
+
+                    ui.bar_chart(BarChart::new(name, bars));
+                }
+            });
+    }
+}
+
+fn run(system: &System, _: &()) -> Option<seidel::Seidel> {
+    system.seidel()
+}
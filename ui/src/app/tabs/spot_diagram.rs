@@ -0,0 +1,136 @@
+use std::f64::consts::TAU;
+
+use egui::{Color32, ComboBox, DragValue, Ui};
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints, Points};
+use optics::{
+    spot::{self, SpotReference},
+    system::System,
+};
+
+use crate::app::{State, analysis::Analysis, widgets::wavelength_color};
+
+/// Side of each sub-plot.
+const PLOT_SIZE: f32 = 320.0;
+
+/// Pupil sampling and reference.
+type Settings = (usize, SpotReference);
+
+pub struct SpotDiagram {
+    sampling: usize,
+    reference: SpotReference,
+    /// Diagram of each field point, and the Airy radius at the primary wavelength.
+    analysis: Analysis<Settings, (Vec<Option<spot::SpotDiagram>>, Option<f32>)>,
+}
+
+impl SpotDiagram {
+    pub fn new() -> Self {
+        Self {
+            sampling: 16,
+            reference: SpotReference::default(),
+            analysis: Analysis::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            ui.label("Pupil sampling:");
+            ui.add(DragValue::new(&mut self.sampling).range(1..=128));
+
+            ui.separator();
+
+            ui.label("Reference:");
+            ComboBox::from_id_salt("spot_reference")
+                .selected_text(self.reference.name())
+                .show_ui(ui, |ui| {
+                    for reference in SpotReference::ALL {
+                        ui.selectable_value(&mut self.reference, reference, reference.name());
+                    }
+                });
+        });
+
+        ui.separator();
+
+        let settings = (self.sampling, self.reference);
+        self.analysis.update(ui.ctx(), state, &settings, run);
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some((diagrams, airy_radius)) = self.analysis.result() else {
+            return;
+        };
+
+        let fmt = &state.formatting;
+
+        ui.horizontal_wrapped(|ui| {
+            for (i, diagram) in diagrams.iter().enumerate() {
+                ui.vertical(|ui| {
+                    ui.strong(format!("Field {}", i + 1));
+
+                    let Some(diagram) = diagram else {
+                        ui.label("The field point could not be traced.");
+                        return;
+                    };
+
+                    ui.label(format!(
+                        "RMS radius: {}\nGEO radius: {}",
+                        fmt.length(diagram.rms_radius),
+                        fmt.length(diagram.geometric_radius),
+                    ));
+
+                    Plot::new(("spot_diagram", i))
+                        .legend(Legend::default())
+                        .data_aspect(1.0)
+                        .width(PLOT_SIZE)
+                        .height(PLOT_SIZE)
+                        .show(ui, |ui| {
+                            for spot in &diagram.spots {
+                                let points: PlotPoints = spot
+                                    .points
+                                    .iter()
+                                    .map(|point| [point.x as f64, point.y as f64])
+                                    .collect();
+
+                                ui.points(
+                                    Points::new(format!("{:.4} µm", spot.wavelength), points)
+                                        .color(wavelength_color(spot.wavelength))
+                                        .radius(1.5),
+                                );
+                            }
+
+                            if let Some(radius) = *airy_radius {
+                                let circle: PlotPoints = (0..=64)
+                                    .map(|i| {
+                                        let angle = TAU * i as f64 / 64.0;
+                                        let radius = radius as f64;
+
+                                        [radius * angle.cos(), radius * angle.sin()]
+                                    })
+                                    .collect();
+
+                                ui.line(
+                                    Line::new("Airy disk", circle)
+                                        .color(Color32::GRAY)
+                                        .style(LineStyle::dashed_loose()),
+                                );
+                            }
+                        });
+                });
+            }
+        });
+    }
+}
+
+fn run(
+    system: &System,
+    &(sampling, reference): &Settings,
+) -> (Vec<Option<spot::SpotDiagram>>, Option<f32>) {
+    let diagrams = system
+        .fields
+        .iter()
+        .map(|point| system.spot_diagram(point, sampling, reference))
+        .collect();
+
+    (diagrams, system.airy_radius(system.primary_wavelength()))
+}
@@ -1,8 +1,11 @@
 use egui::Sense;
 use egui_extras::{Column, TableBuilder};
-use optics::surface::SurfaceData;
+use optics::surface::{SurfaceData, SurfaceKind};
 
-use crate::app::{State, widgets::SurfaceRow};
+use crate::app::{
+    State,
+    widgets::{SurfaceRow, surface_apertures, surface_coefficients},
+};
 
 pub struct SurfaceEditor {
     pub row: usize,
@@ -14,6 +17,24 @@ impl SurfaceEditor {
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let surface = &mut state.system.surfaces[self.row];
+
+        if surface.kind().has_coefficients() {
+            ui.collapsing(format!("Surface {} Coefficients", self.row), |ui| {
+                surface_coefficients(ui, surface, &state.formatting);
+            });
+
+            ui.separator();
+        }
+
+        if surface.kind() != SurfaceKind::Object {
+            ui.collapsing(format!("Surface {} Apertures", self.row), |ui| {
+                surface_apertures(ui, surface, &state.formatting);
+            });
+
+            ui.separator();
+        }
+
         let table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
@@ -50,6 +71,26 @@ impl SurfaceEditor {
                                     "rotation_x" => "Rotation X",
                                     "rotation_y" => "Rotation Y",
                                     "rotation_z" => "Rotation Z",
+                                    "conic" => "Conic",
+                                    "asphere_r2" => "r² Term",
+                                    "asphere_r4" => "r⁴ Term",
+                                    "asphere_r6" => "r⁶ Term",
+                                    "asphere_r8" => "r⁸ Term",
+                                    "asphere_r10" => "r¹⁰ Term",
+                                    "asphere_r12" => "r¹² Term",
+                                    "asphere_r14" => "r¹⁴ Term",
+                                    "asphere_r16" => "r¹⁶ Term",
+                                    "focal_length" => "Focal Length",
+                                    "opd_mode" => "OPD Mode",
+                                    "mirror" => "Mirror",
+                                    "curvature_x" => "Curvature X",
+                                    "conic_x" => "Conic X",
+                                    "line_density" => "Line Density",
+                                    "diffraction_order" => "Order",
+                                    "grating_angle" => "Grating Angle",
+                                    "design_wavelength" => "Design Wavelength",
+                                    "norm_radius" => "Norm Radius",
+                                    "zernike_ordering" => "Ordering",
                                     _ => name,
                                 }),
                                 None => ui.strong(format!("Arg[{i}]")),
@@ -1,27 +1,59 @@
 use std::f64;
 
 use egui::{Color32, Stroke};
-use egui_plot::{Line, LineStyle, Plot, PlotPoints};
+use egui_plot::{Line, LineStyle, Plot, PlotPoints, Points};
 use optics::{
     glam::{Vec3, Vec3Swizzles, vec3},
-    surface::{CURVATURE, SEMI_DIAMETER, SurfaceKind, THICKNESS},
+    surface::{FOCAL_LENGTH, Meridian, SEMI_DIAMETER, Surface, SurfaceKind, THICKNESS},
 };
 
 use crate::app::State;
 
-pub struct System2dViewer {}
+pub struct System2dViewer {
+    section: Meridian,
+}
 
 impl System2dViewer {
     pub const fn new() -> Self {
-        Self {}
+        Self {
+            section: Meridian::Y,
+        }
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        ui.horizontal(|ui| {
+            ui.label("Section:");
+            ui.selectable_value(&mut self.section, Meridian::Y, "YZ");
+            ui.selectable_value(&mut self.section, Meridian::X, "XZ");
+        });
+
+        ui.separator();
+
+        let section = self.section;
+        let axis = match section {
+            Meridian::X => "x",
+            Meridian::Y => "y",
+        };
+
+        // Points on the section plane, where h is the coordinate along the transverse axis
+        let section_point = |h: f32, z: f32| match section {
+            Meridian::X => vec3(h, 0.0, z),
+            Meridian::Y => vec3(0.0, h, z),
+        };
+        let project = |point: Vec3| {
+            match section {
+                Meridian::X => point.zx(),
+                Meridian::Y => point.zy(),
+            }
+            .as_dvec2()
+            .to_array()
+        };
+
         let plot = Plot::new("system_2d_viewer")
             .label_formatter(|name, value| {
                 if name.is_empty() {
                     format!(
-                        "y: {:.*}\nz: {:.*}",
+                        "{axis}: {:.*}\nz: {:.*}",
                         state.formatting.decimal_places,
                         value.y,
                         state.formatting.decimal_places,
@@ -29,7 +61,7 @@ impl System2dViewer {
                     )
                 } else {
                     format!(
-                        "{name}\ny: {:.*}\nz: {:.*}",
+                        "{name}\n{axis}: {:.*}\nz: {:.*}",
                         state.formatting.decimal_places,
                         value.y,
                         state.formatting.decimal_places,
@@ -43,6 +75,7 @@ impl System2dViewer {
         plot.show(ui, |plot| {
             let mut axis_points = Vec::new();
             let mut lines = Vec::new();
+            let mut edges = Vec::new();
 
             axis_points.push([0.0, 0.0]);
             // plot.vline(VLine::new("y", 0.0).stroke(Stroke::new(1.0, Color32::GREEN)));
@@ -50,9 +83,27 @@ impl System2dViewer {
             for (i, (surface, transform)) in state.system.surfaces().enumerate() {
                 let data = surface.data();
 
-                {
-                    let [x, y] = transform.project_point3(Vec3::ZERO).zy().to_array();
-                    axis_points.push([x as f64, y as f64]);
+                axis_points.push(project(transform.project_point3(Vec3::ZERO)));
+
+                // Aperture edges are marked on the surface, where the section crosses them
+                if !surface.apertures().is_empty() {
+                    let semi_diameter: f32 = data[SEMI_DIAMETER].into();
+                    let extent = surface
+                        .apertures()
+                        .iter()
+                        .map(|aperture| aperture.shape.extent() + aperture.decenter.length())
+                        .fold(semi_diameter, f32::max);
+
+                    edges.extend(
+                        aperture_edges(surface, section_point, extent)
+                            .into_iter()
+                            .filter_map(|h| {
+                                let point = section_point(h, 0.0);
+                                let sag = surface.sag(point.x, point.y)?;
+
+                                Some(project(transform.project_point3(section_point(h, sag))))
+                            }),
+                    );
                 }
 
                 match surface.kind() {
@@ -79,13 +130,20 @@ impl System2dViewer {
                             axis_points[0][0] = 10000.0;
                         }
                     }
-                    SurfaceKind::Spherical => {
+                    SurfaceKind::Spherical
+                    | SurfaceKind::EvenAsphere
+                    | SurfaceKind::Toroidal
+                    | SurfaceKind::ZernikeSag
+                    | SurfaceKind::XyPolynomial
+                    | SurfaceKind::Biconic
+                    | SurfaceKind::Grating
+                    | SurfaceKind::Binary2 => {
                         const N: usize = 512;
 
-                        let radius = {
-                            let curvature: f32 = data[CURVATURE].into();
-                            curvature.recip()
-                        };
+                        let radius = surface
+                            .paraxial_curvature(section)
+                            .unwrap_or_default()
+                            .recip();
 
                         let semi_diameter = {
                             let semi_diameter: f32 = data[SEMI_DIAMETER].into();
@@ -93,10 +151,15 @@ impl System2dViewer {
                             if !semi_diameter.is_finite() || semi_diameter <= 0.0 {
                                 radius.abs() as f64
                             } else {
-                                semi_diameter.min(radius.abs()) as f64
+                                semi_diameter as f64
                             }
                         };
 
+                        // Flat surfaces without a semi-diameter have no extent to draw
+                        if !semi_diameter.is_finite() {
+                            continue;
+                        }
+
                         /*
                         let points = PlotPoints::from_parametric_callback(
                             |angle| {
@@ -125,23 +188,58 @@ impl System2dViewer {
                         );
                         */
 
-                        let points = PlotPoints::from_parametric_callback(
-                            |y| {
-                                let x = -radius as f64 * (-1.0 + (y / radius as f64).asin().cos());
-                                let [x, y] = transform
-                                    .project_point3(vec3(0.0, y as f32, x as f32))
-                                    .zy()
-                                    .as_dvec2()
-                                    .to_array();
-                                (x, y)
-                            },
-                            -semi_diameter..=semi_diameter,
-                            N,
+                        // Points outside of the surface domain (e.g. beyond the radius of a
+                        // sphere) have no sag and are skipped
+                        let points: PlotPoints = (0..=N)
+                            .filter_map(|j| {
+                                let h = (semi_diameter * (2.0 * j as f64 / N as f64 - 1.0)) as f32;
+                                let sag = match section {
+                                    Meridian::X => surface.sag(h, 0.0),
+                                    Meridian::Y => surface.sag(0.0, h),
+                                }
+                                .filter(|sag| sag.is_finite())?;
+
+                                Some(project(transform.project_point3(section_point(h, sag))))
+                            })
+                            .collect();
+
+                        // Mirrors are drawn thicker than refractive surfaces
+                        let width = if surface.is_mirror() { 2.5 } else { 1.0 };
+
+                        lines.push(
+                            Line::new(format!("Surface {i}"), points)
+                                .stroke(Stroke::new(width, Color32::WHITE)),
                         );
+                    }
+                    SurfaceKind::Paraxial => {
+                        let focal_length: f32 = data[FOCAL_LENGTH].into();
+                        let semi_diameter: f32 = data[SEMI_DIAMETER].into();
+
+                        if !semi_diameter.is_finite() || semi_diameter <= 0.0 {
+                            continue;
+                        }
+
+                        // Ideal lenses are drawn as a double arrow, pointing outwards when
+                        // converging and inwards when diverging
+                        let head = 0.1 * semi_diameter;
+                        let tip = if focal_length >= 0.0 { -head } else { head };
+
+                        let points = [
+                            (-head, semi_diameter + tip),
+                            (0.0, semi_diameter),
+                            (head, semi_diameter + tip),
+                            (0.0, semi_diameter),
+                            (0.0, -semi_diameter),
+                            (-head, -semi_diameter - tip),
+                            (0.0, -semi_diameter),
+                            (head, -semi_diameter - tip),
+                        ]
+                        .map(|(z, h)| project(transform.project_point3(section_point(h, z))))
+                        .to_vec();
 
                         lines.push(
                             Line::new(format!("Surface {i}"), points)
-                                .stroke(Stroke::new(1.0, Color32::WHITE)),
+                                .stroke(Stroke::new(1.0, Color32::LIGHT_BLUE)),
                         );
                     }
                     _ => {}
@@ -157,6 +255,40 @@ impl System2dViewer {
             for line in lines {
                 plot.line(line);
             }
+
+            plot.points(
+                Points::new("Aperture Edges", edges)
+                    .radius(3.0)
+                    .color(Color32::ORANGE),
+            );
         });
     }
 }
+
+/// Coordinates along the section line where rays go from blocked to unblocked by the apertures of
+/// `surface`, or the other way around. The line is sampled up to `extent` from the axis.
+fn aperture_edges(
+    surface: &Surface,
+    section_point: impl Fn(f32, f32) -> Vec3,
+    extent: f32,
+) -> Vec<f32> {
+    const N: usize = 1024;
+
+    let passes = |h: f32| {
+        let point = section_point(h, 0.0);
+        surface.passes_apertures(point.x, point.y)
+    };
+
+    let step = 2.0 * extent / N as f32;
+    let mut edges = Vec::new();
+
+    for j in 0..N {
+        let h = j as f32 * step - extent;
+
+        if passes(h) != passes(h + step) {
+            edges.push(h + 0.5 * step);
+        }
+    }
+
+    edges
+}
@@ -0,0 +1,193 @@
+use egui::{Color32, ColorImage, DragValue, TextureHandle, Ui};
+use egui_plot::{Plot, PlotImage, PlotPoint};
+use optics::{
+    opd::{self, OpdFan},
+    system::System,
+};
+
+use crate::app::{
+    State,
+    analysis::Analysis,
+    widgets::{
+        colormap, colormap_legend, fan_plot, field_selector, update_texture, wavelength_selector,
+    },
+};
+
+/// Size of the wavefront map.
+const PLOT_SIZE: f32 = 280.0;
+
+/// Field, wavelength, map size and rays per fan.
+type Settings = (usize, usize, usize, usize);
+
+pub struct Wavefront {
+    field: usize,
+    wavelength: usize,
+    size: usize,
+    samples: usize,
+    analysis: Analysis<Settings, Option<(opd::Wavefront, Vec<OpdFan>)>>,
+    texture: Option<TextureHandle>,
+}
+
+impl Wavefront {
+    pub fn new() -> Self {
+        Self {
+            field: 0,
+            wavelength: 0,
+            size: 64,
+            samples: 41,
+            analysis: Analysis::new(),
+            texture: None,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &state.system;
+
+        ui.horizontal(|ui| {
+            field_selector(ui, "wavefront_field", &mut self.field, system);
+
+            ui.separator();
+
+            wavelength_selector(ui, "wavefront_wavelength", &mut self.wavelength, system);
+
+            ui.separator();
+
+            ui.label("Map size:");
+            ui.add(DragValue::new(&mut self.size).range(8..=256));
+
+            ui.separator();
+
+            ui.label("Rays per fan:");
+            ui.add(DragValue::new(&mut self.samples).range(3..=501));
+        });
+
+        ui.separator();
+
+        if system.fields.get(self.field).is_none()
+            || system.wavelengths.get(self.wavelength).is_none()
+        {
+            ui.label("The system has no field points or wavelengths.");
+            return;
+        }
+
+        let settings = (self.field, self.wavelength, self.size, self.samples);
+
+        if self.analysis.update(ui.ctx(), state, &settings, run) {
+            match self.analysis.result() {
+                Some(Some((wavefront, _))) => {
+                    let image = map_image(wavefront);
+                    update_texture(ui.ctx(), &mut self.texture, "wavefront_map", image);
+                }
+                _ => self.texture = None,
+            }
+        }
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        let Some(result) = self.analysis.result() else {
+            return;
+        };
+
+        let (Some((wavefront, fans)), Some(texture)) = (result, &self.texture) else {
+            ui.label("The field point could not be traced.");
+            return;
+        };
+
+        let decimal_places = state.formatting.decimal_places;
+        let (min, max) = value_range(wavefront);
+
+        ui.label(format!(
+            "P-V: {:.decimal_places$} waves\nRMS: {:.decimal_places$} waves\nStrehl ratio: {:.decimal_places$}",
+            wavefront.peak_to_valley, wavefront.rms, wavefront.strehl,
+        ));
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                Plot::new("wavefront_map")
+                    .data_aspect(1.0)
+                    .x_axis_label("Px")
+                    .y_axis_label("Py")
+                    .width(PLOT_SIZE)
+                    .height(PLOT_SIZE)
+                    .show(ui, |ui| {
+                        ui.image(PlotImage::new(
+                            "Wavefront",
+                            texture.id(),
+                            PlotPoint::new(0.0, 0.0),
+                            [2.0, 2.0],
+                        ));
+                    });
+
+                colormap_legend(
+                    ui,
+                    PLOT_SIZE / 2.0,
+                    format!("{min:.decimal_places$}"),
+                    format!("{max:.decimal_places$} waves"),
+                );
+            });
+
+            fan_plot(
+                ui,
+                "tangential_opd_fan",
+                "Py",
+                "W (waves)",
+                fans.iter().map(|fan| (fan.wavelength, &fan.tangential[..])),
+            );
+            fan_plot(
+                ui,
+                "sagittal_opd_fan",
+                "Px",
+                "W (waves)",
+                fans.iter().map(|fan| (fan.wavelength, &fan.sagittal[..])),
+            );
+        });
+    }
+}
+
+fn run(
+    system: &System,
+    &(field, wavelength, size, samples): &Settings,
+) -> Option<(opd::Wavefront, Vec<OpdFan>)> {
+    let point = system.fields.get(field)?;
+    let wavelength = system.wavelengths.get(wavelength)?.value;
+
+    Some((
+        system.wavefront(point, wavelength, size)?,
+        system.opd_fans(point, samples)?,
+    ))
+}
+
+/// Smallest and largest values of the map.
+fn value_range(wavefront: &opd::Wavefront) -> (f32, f32) {
+    wavefront
+        .values
+        .iter()
+        .flatten()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+fn map_image(wavefront: &opd::Wavefront) -> ColorImage {
+    let (min, max) = value_range(wavefront);
+
+    // Rows of the image go from the top of the pupil down, so the map is flipped vertically
+    let range = (max - min).max(f32::EPSILON);
+    let pixels: Vec<_> = wavefront
+        .values
+        .chunks(wavefront.size)
+        .rev()
+        .flatten()
+        .flat_map(|value| {
+            value
+                .map_or(Color32::TRANSPARENT, |value| {
+                    colormap((value - min) / range)
+                })
+                .to_array()
+        })
+        .collect();
+
+    ColorImage::from_rgba_unmultiplied([wavefront.size, wavefront.size], &pixels)
+}
@@ -0,0 +1,78 @@
+use egui::{Button, DragValue, Grid, Ui};
+use optics::wavelengths::{WavelengthPreset, WeightedWavelength};
+
+use crate::app::{State, widgets};
+
+pub struct Wavelengths;
+
+impl Wavelengths {
+    pub fn new() -> Self {
+        Wavelengths
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &mut state.system;
+
+        ui.horizontal(|ui| {
+            ui.menu_button("Load preset", |ui| {
+                for preset in WavelengthPreset::ALL {
+                    if ui.button(preset.name()).clicked() {
+                        system.set_wavelengths(preset);
+                    }
+                }
+            });
+
+            if ui.button("Add wavelength").clicked() {
+                let wavelength = system.primary_wavelength();
+                system.wavelengths.push(WeightedWavelength::new(wavelength));
+            }
+        });
+
+        ui.separator();
+
+        let mut removed = None;
+
+        Grid::new("wavelengths")
+            .striped(true)
+            .num_columns(5)
+            .show(ui, |ui| {
+                for name in ["#", "Primary", "Wavelength", "Weight", ""] {
+                    ui.strong(name);
+                }
+
+                ui.end_row();
+
+                let len = system.wavelengths.len();
+
+                for (i, wavelength) in system.wavelengths.iter_mut().enumerate() {
+                    ui.label((i + 1).to_string());
+                    ui.radio_value(&mut system.primary_wavelength, i, "");
+                    widgets::wavelength(ui, &mut wavelength.value, &state.formatting);
+                    ui.add(
+                        DragValue::new(&mut wavelength.weight)
+                            .speed(0.01)
+                            .range(0.0..=f32::MAX),
+                    );
+
+                    // Systems always keep at least one wavelength
+                    if ui.add_enabled(len > 1, Button::new("Remove")).clicked() {
+                        removed = Some(i);
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = removed {
+            system.wavelengths.remove(i);
+
+            if system.primary_wavelength > i {
+                system.primary_wavelength -= 1;
+            }
+
+            system.primary_wavelength = system
+                .primary_wavelength
+                .min(system.wavelengths.len().saturating_sub(1));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use egui::{Button, ComboBox, DragValue, Grid, TextEdit, Ui};
+use optics::{
+    ray::Wavelength,
+    system::System,
+    zernike::{ZernikeFit, ZernikeOrdering},
+};
+
+use crate::app::{
+    State,
+    analysis::Analysis,
+    log::{Log, LogLevel},
+    widgets::{field_selector, wavelength_selector},
+};
+
+/// Largest number of terms that can be fitted with orderings that are not limited.
+const MAX_TERMS: usize = 231;
+
+/// Ordering, number of terms and map size.
+type Settings = (ZernikeOrdering, usize, usize);
+
+/// Wavefront map of a field point at one wavelength, reduced to its RMS and its fit.
+struct Fit {
+    wavelength: Wavelength,
+    rms: f32,
+    fit: Option<ZernikeFit>,
+}
+
+pub struct Zernike {
+    field: usize,
+    wavelength: usize,
+    ordering: ZernikeOrdering,
+    terms: usize,
+    size: usize,
+    path: String,
+    /// Fits of every field point at every wavelength, so that both the table and the export come
+    /// from the same run.
+    analysis: Analysis<Settings, Vec<Vec<Option<Fit>>>>,
+}
+
+impl Zernike {
+    pub fn new() -> Self {
+        Self {
+            field: 0,
+            wavelength: 0,
+            ordering: ZernikeOrdering::Fringe,
+            terms: 37,
+            size: 64,
+            path: "zernike.csv".into(),
+            analysis: Analysis::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, state: &mut State) {
+        let system = &state.system;
+
+        ui.horizontal(|ui| {
+            field_selector(ui, "zernike_field", &mut self.field, system);
+
+            ui.separator();
+
+            wavelength_selector(ui, "zernike_wavelength", &mut self.wavelength, system);
+
+            ui.separator();
+
+            ui.label("Ordering:");
+            ComboBox::from_id_salt("zernike_ordering")
+                .selected_text(self.ordering.name())
+                .show_ui(ui, |ui| {
+                    for ordering in ZernikeOrdering::ALL {
+                        ui.selectable_value(&mut self.ordering, ordering, ordering.name());
+                    }
+                });
+
+            let max_terms = self.ordering.max_terms().unwrap_or(MAX_TERMS);
+            self.terms = self.terms.min(max_terms);
+
+            ui.label("Terms:");
+            ui.add(DragValue::new(&mut self.terms).range(1..=max_terms));
+
+            ui.separator();
+
+            ui.label("Map size:");
+            ui.add(DragValue::new(&mut self.size).range(8..=256));
+        });
+
+        let settings = (self.ordering, self.terms, self.size);
+        self.analysis.update(ui.ctx(), state, &settings, run);
+
+        // Exports wait for the fits of the current settings
+        let fits = self
+            .analysis
+            .result()
+            .filter(|_| !self.analysis.is_running());
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(fits.is_some(), Button::new("Copy CSV"))
+                .clicked()
+                && let Some(fits) = fits
+            {
+                ui.ctx().copy_text(csv(fits));
+            }
+
+            ui.separator();
+
+            ui.add(TextEdit::singleline(&mut self.path).desired_width(240.0));
+
+            if ui
+                .add_enabled(fits.is_some(), Button::new("Export CSV"))
+                .clicked()
+                && let Some(fits) = fits
+            {
+                let log = match std::fs::write(&self.path, csv(fits)) {
+                    Ok(()) => Log::new(
+                        LogLevel::Info,
+                        "Zernike export",
+                        format!("Coefficients written to {}", self.path),
+                    ),
+                    Err(error) => Log::new(LogLevel::Error, "Zernike export", error.to_string()),
+                };
+
+                state.log.push(log);
+            }
+        });
+
+        ui.separator();
+
+        let system = &state.system;
+
+        if system.fields.get(self.field).is_none()
+            || system.wavelengths.get(self.wavelength).is_none()
+        {
+            ui.label("The system has no field points or wavelengths.");
+            return;
+        }
+
+        if self.analysis.is_running() {
+            ui.spinner();
+        }
+
+        // Fields and wavelengths added since the last run have no fits yet
+        let Some(result) = self
+            .analysis
+            .result()
+            .and_then(|fits| fits.get(self.field)?.get(self.wavelength))
+        else {
+            return;
+        };
+
+        let Some(Fit { rms, fit, .. }) = result else {
+            ui.label("The field point could not be traced.");
+            return;
+        };
+
+        let Some(fit) = fit else {
+            ui.label("The wavefront map has too few samples for the number of terms.");
+            return;
+        };
+
+        let decimal_places = state.formatting.decimal_places;
+
+        ui.label(format!(
+            "Wavefront RMS: {:.decimal_places$} waves\nResidual RMS: {:.decimal_places$} waves\nResidual P-V: {:.decimal_places$} waves",
+            rms, fit.residual_rms, fit.residual_peak_to_valley,
+        ));
+
+        ui.separator();
+
+        Grid::new("zernike")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                for name in ["Term", "n", "m", "Coefficient (waves)"] {
+                    ui.strong(name);
+                }
+
+                ui.end_row();
+
+                for (i, (term, coefficient)) in fit.terms().enumerate() {
+                    ui.label(format!("Z{}", i + 1));
+                    ui.label(term.n.to_string());
+                    ui.label(term.m.to_string());
+                    ui.label(format!("{coefficient:.decimal_places$}"));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn run(system: &System, &(ordering, terms, size): &Settings) -> Vec<Vec<Option<Fit>>> {
+    system
+        .fields
+        .iter()
+        .map(|point| {
+            system
+                .wavelengths
+                .iter()
+                .map(|wavelength| {
+                    let wavefront = system.wavefront(point, wavelength.value, size)?;
+
+                    Some(Fit {
+                        wavelength: wavelength.value,
+                        rms: wavefront.rms,
+                        fit: wavefront.zernike_fit(ordering, terms),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Coefficients of every field point and wavelength in `fits` as comma separated values, with one
+/// row per term.
+fn csv(fits: &[Vec<Option<Fit>>]) -> String {
+    let mut csv = String::from(
+        "Field,Wavelength (um),Ordering,Term,n,m,Coefficient (waves),Residual RMS (waves),Residual P-V (waves)\n",
+    );
+
+    for (i, fits) in fits.iter().enumerate() {
+        for Fit {
+            wavelength, fit, ..
+        } in fits.iter().flatten()
+        {
+            let Some(fit) = fit else {
+                continue;
+            };
+
+            for (j, (term, coefficient)) in fit.terms().enumerate() {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{}",
+                    i + 1,
+                    wavelength,
+                    fit.ordering.name(),
+                    j + 1,
+                    term.n,
+                    term.m,
+                    coefficient,
+                    fit.residual_rms,
+                    fit.residual_peak_to_valley,
+                );
+            }
+        }
+    }
+
+    csv
+}
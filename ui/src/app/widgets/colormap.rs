@@ -0,0 +1,46 @@
+use egui::{Color32, Sense, Ui, vec2};
+
+/// Color of `value`, from 0 to 1, on a blue, cyan, green, yellow and red scale. Values outside of
+/// the range are clamped.
+pub fn colormap(value: f32) -> Color32 {
+    let value = if value.is_finite() {
+        value.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Each quarter of the range blends two neighbouring colors
+    let (r, g, b) = match value * 4.0 {
+        t if t < 1.0 => (0.0, t, 1.0),
+        t if t < 2.0 => (0.0, 1.0, 2.0 - t),
+        t if t < 3.0 => (t - 2.0, 1.0, 0.0),
+        t => (1.0, 4.0 - t, 0.0),
+    };
+
+    Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+/// Scale of [`colormap`] of the given `width`, between the labels of its lowest and highest
+/// values.
+pub fn colormap_legend(ui: &mut Ui, width: f32, min: String, max: String) {
+    /// Number of colors shown on the scale.
+    const STEPS: usize = 32;
+
+    ui.horizontal(|ui| {
+        ui.label(min);
+
+        let (rect, _) =
+            ui.allocate_exact_size(vec2(width, ui.spacing().interact_size.y), Sense::hover());
+
+        for step in 0..STEPS {
+            let mut cell = rect;
+            cell.set_left(rect.left() + rect.width() * step as f32 / STEPS as f32);
+            cell.set_right(rect.left() + rect.width() * (step + 1) as f32 / STEPS as f32);
+
+            ui.painter()
+                .rect_filled(cell, 0.0, colormap(step as f32 / (STEPS - 1) as f32));
+        }
+
+        ui.label(max);
+    });
+}
@@ -0,0 +1,42 @@
+use std::hash::Hash;
+
+use egui::Ui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use optics::{glam::Vec2, ray::Wavelength};
+
+use crate::app::widgets::wavelength_color;
+
+/// Size of each fan plot.
+const PLOT_SIZE: f32 = 280.0;
+
+/// Plot of a fan of rays across the pupil, with one line per wavelength. `fans` holds the
+/// wavelength of each line along with its points, as (normalized pupil coordinate, value).
+pub fn fan_plot<'a>(
+    ui: &mut Ui,
+    id: impl Hash,
+    x_label: &str,
+    y_label: &str,
+    fans: impl IntoIterator<Item = (Wavelength, &'a [Vec2])>,
+) {
+    Plot::new(id)
+        .legend(Legend::default())
+        .x_axis_label(x_label)
+        .y_axis_label(y_label)
+        .include_x(-1.0)
+        .include_x(1.0)
+        .width(PLOT_SIZE)
+        .height(PLOT_SIZE)
+        .show(ui, |ui| {
+            for (wavelength, points) in fans {
+                let line: PlotPoints = points
+                    .iter()
+                    .map(|point| [point.x as f64, point.y as f64])
+                    .collect();
+
+                ui.line(
+                    Line::new(format!("{wavelength:.4} µm"), line)
+                        .color(wavelength_color(wavelength)),
+                );
+            }
+        });
+}
@@ -1,7 +1,21 @@
+mod colormap;
+mod fan_plot;
 mod material_index;
+mod psf;
+mod selectors;
+mod surface_apertures;
+mod surface_coefficients;
 mod surface_row;
+mod texture;
 mod wavelength;
 
+pub use colormap::*;
+pub use fan_plot::*;
 pub use material_index::*;
+pub use psf::*;
+pub use selectors::*;
+pub use surface_apertures::*;
+pub use surface_coefficients::*;
 pub use surface_row::*;
+pub use texture::*;
 pub use wavelength::*;
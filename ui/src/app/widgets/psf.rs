@@ -0,0 +1,55 @@
+use std::hash::Hash;
+
+use egui::{ColorImage, TextureId, Ui};
+use egui_plot::{Plot, PlotImage, PlotPoint};
+use optics::diffraction::Psf;
+
+use crate::app::widgets::{colormap, colormap_legend};
+
+/// Image of `psf` relative to its peak, to be shown with [`psf_plot`].
+pub fn psf_image(psf: &Psf) -> ColorImage {
+    let peak = psf.peak.max(f32::MIN_POSITIVE);
+
+    // Rows of the image go from the top of the grid down
+    let pixels: Vec<_> = psf
+        .values
+        .chunks(psf.size)
+        .rev()
+        .flatten()
+        .flat_map(|value| colormap(value / peak).to_array())
+        .collect();
+
+    ColorImage::from_rgba_unmultiplied([psf.size, psf.size], &pixels)
+}
+
+/// Plot of `psf` with its image loaded in `texture`, see [`psf_image`]. The plot is a square of
+/// side `size` with axes in micrometers, showing `extent` millimeters around its center by
+/// default.
+pub fn psf_plot(ui: &mut Ui, id: impl Hash, texture: TextureId, psf: &Psf, extent: f32, size: f32) {
+    // Samples are centered on their positions, so the grid starts half a sample before the first
+    let spacing = psf.spacing as f64 * 1e3;
+    let corner = psf.position(0, 0).as_dvec2() * 1e3 - spacing / 2.0;
+    let side = spacing * psf.size as f64;
+    let extent = extent as f64 * 1e3;
+
+    ui.vertical(|ui| {
+        Plot::new(id)
+            .data_aspect(1.0)
+            .x_axis_label("X (µm)")
+            .y_axis_label("Y (µm)")
+            .default_x_bounds(-extent, extent)
+            .default_y_bounds(-extent, extent)
+            .width(size)
+            .height(size)
+            .show(ui, |ui| {
+                ui.image(PlotImage::new(
+                    "PSF",
+                    texture,
+                    PlotPoint::new(corner.x + side / 2.0, corner.y + side / 2.0),
+                    [side as f32, side as f32],
+                ));
+            });
+
+        colormap_legend(ui, size / 2.0, "0".into(), format!("{:.3}", psf.peak));
+    });
+}
@@ -0,0 +1,36 @@
+use egui::{ComboBox, Ui};
+use optics::system::System;
+
+/// Selects one of the field points of `system`, keeping `field` within their range.
+pub fn field_selector(ui: &mut Ui, id: &str, field: &mut usize, system: &System) {
+    *field = (*field).min(system.fields.len().saturating_sub(1));
+
+    ui.label("Field:");
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", *field + 1))
+        .show_ui(ui, |ui| {
+            for i in 0..system.fields.len() {
+                ui.selectable_value(field, i, format!("{}", i + 1));
+            }
+        });
+}
+
+/// Selects one of the wavelengths of `system`, keeping `wavelength` within their range.
+pub fn wavelength_selector(ui: &mut Ui, id: &str, wavelength: &mut usize, system: &System) {
+    *wavelength = (*wavelength).min(system.wavelengths.len().saturating_sub(1));
+
+    ui.label("Wavelength:");
+    ComboBox::from_id_salt(id)
+        .selected_text(
+            system
+                .wavelengths
+                .get(*wavelength)
+                .map(|wavelength| format!("{:.4} µm", wavelength.value))
+                .unwrap_or_default(),
+        )
+        .show_ui(ui, |ui| {
+            for (i, value) in system.wavelengths.iter().enumerate() {
+                ui.selectable_value(wavelength, i, format!("{:.4} µm", value.value));
+            }
+        });
+}
@@ -0,0 +1,158 @@
+use egui::{Button, ComboBox, DragValue, Grid, Ui};
+use optics::surface::{Aperture, ApertureShape, SEMI_DIAMETER, Surface};
+
+use crate::app::{
+    formatting::Formatting,
+    widgets::{angle_value, length_value},
+};
+
+/// Editor for the apertures and obscurations of a surface.
+pub fn surface_apertures(ui: &mut Ui, surface: &mut Surface, fmt: &Formatting) {
+    // New outlines are sized after the semi-diameter of the surface
+    let size = {
+        let semi_diameter: f32 = surface.data()[SEMI_DIAMETER].into();
+
+        if semi_diameter.is_finite() && semi_diameter > 0.0 {
+            semi_diameter
+        } else {
+            1.0
+        }
+    };
+
+    let apertures = surface.apertures_mut();
+
+    ui.horizontal(|ui| {
+        if ui.button("Add aperture").clicked() {
+            apertures.push(Aperture::new(ApertureShape::Circular { radius: size }));
+        }
+
+        if ui.button("Add obscuration").clicked() {
+            apertures.push(Aperture::obscuration(ApertureShape::Circular {
+                radius: 0.25 * size,
+            }));
+        }
+    });
+
+    let mut removed = None;
+
+    Grid::new("surface_apertures")
+        .striped(true)
+        .num_columns(7)
+        .show(ui, |ui| {
+            if !apertures.is_empty() {
+                for name in [
+                    "Shape",
+                    "Dimensions",
+                    "Obscuration",
+                    "Decenter X",
+                    "Decenter Y",
+                    "Rotation",
+                    "",
+                ] {
+                    ui.strong(name);
+                }
+
+                ui.end_row();
+            }
+
+            for (i, aperture) in apertures.iter_mut().enumerate() {
+                ui.push_id(i, |ui| shape(ui, &mut aperture.shape, size));
+                ui.push_id(i, |ui| {
+                    ui.horizontal(|ui| dimensions(ui, &mut aperture.shape, fmt))
+                });
+                ui.checkbox(&mut aperture.obscuration, "");
+                length_value(ui, &mut aperture.decenter.x, fmt);
+                length_value(ui, &mut aperture.decenter.y, fmt);
+                angle_value(ui, &mut aperture.rotation, fmt);
+
+                if ui.add(Button::new("Remove")).clicked() {
+                    removed = Some(i);
+                }
+
+                ui.end_row();
+            }
+        });
+
+    if let Some(i) = removed {
+        apertures.remove(i);
+    }
+}
+
+fn shape(ui: &mut Ui, shape: &mut ApertureShape, size: f32) {
+    let shapes = [
+        ApertureShape::Circular { radius: size },
+        ApertureShape::Annular {
+            inner_radius: 0.5 * size,
+            outer_radius: size,
+        },
+        ApertureShape::Rectangular {
+            half_width: size,
+            half_height: size,
+        },
+        ApertureShape::Elliptical {
+            semi_axis_x: size,
+            semi_axis_y: 0.5 * size,
+        },
+        ApertureShape::Spider {
+            arms: 4,
+            width: 0.05 * size,
+        },
+    ];
+
+    ComboBox::from_id_salt("aperture_shape")
+        .selected_text(shape.name())
+        .show_ui(ui, |ui| {
+            for option in shapes {
+                // Keep the dimensions when the shape does not change
+                if ui
+                    .selectable_label(option.name() == shape.name(), option.name())
+                    .clicked()
+                    && option.name() != shape.name()
+                {
+                    *shape = option;
+                }
+            }
+        });
+}
+
+fn dimensions(ui: &mut Ui, shape: &mut ApertureShape, fmt: &Formatting) {
+    match shape {
+        ApertureShape::Circular { radius } => {
+            ui.label("r");
+            length_value(ui, radius, fmt);
+        }
+        ApertureShape::Annular {
+            inner_radius,
+            outer_radius,
+        } => {
+            ui.label("r₁");
+            length_value(ui, inner_radius, fmt);
+            ui.label("r₂");
+            length_value(ui, outer_radius, fmt);
+        }
+        ApertureShape::Rectangular {
+            half_width,
+            half_height,
+        } => {
+            ui.label("½w");
+            length_value(ui, half_width, fmt);
+            ui.label("½h");
+            length_value(ui, half_height, fmt);
+        }
+        ApertureShape::Elliptical {
+            semi_axis_x,
+            semi_axis_y,
+        } => {
+            ui.label("a");
+            length_value(ui, semi_axis_x, fmt);
+            ui.label("b");
+            length_value(ui, semi_axis_y, fmt);
+        }
+        ApertureShape::Spider { arms, width } => {
+            ui.label("Arms");
+            ui.add(DragValue::new(arms).range(1..=16));
+            ui.label("w");
+            length_value(ui, width, fmt);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use egui::{DragValue, Grid, Ui};
+use optics::{
+    surface::{Surface, SurfaceKind, ZERNIKE_ORDERING, xy_polynomial},
+    zernike::ZernikeOrdering,
+};
+
+use crate::app::{formatting::Formatting, si, widgets::superscript};
+
+/// Editor for the block of coefficients of surfaces whose kind uses one.
+pub fn surface_coefficients(ui: &mut Ui, surface: &mut Surface, fmt: &Formatting) {
+    let kind = surface.kind();
+
+    let max_terms = match kind {
+        SurfaceKind::ZernikeSag => {
+            ZernikeOrdering::from_u32(surface.data()[ZERNIKE_ORDERING].into()).max_terms()
+        }
+        SurfaceKind::XyPolynomial => Some(xy_polynomial::MAX_TERMS),
+        _ => None,
+    };
+
+    let coefficients = surface.coefficients_mut();
+
+    ui.horizontal(|ui| {
+        let can_add = max_terms.is_none_or(|max_terms| coefficients.len() < max_terms);
+
+        if ui
+            .add_enabled(can_add, egui::Button::new("Add term"))
+            .clicked()
+        {
+            coefficients.push(0.0);
+        }
+
+        if ui
+            .add_enabled(!coefficients.is_empty(), egui::Button::new("Remove term"))
+            .clicked()
+        {
+            coefficients.pop();
+        }
+
+        ui.label(format!("{} terms", coefficients.len()));
+    });
+
+    // Binary 2 coefficients are phases, so they do not follow the length unit
+    let (factor, suffix) = match kind {
+        SurfaceKind::Binary2 => (1.0, " rad".to_owned()),
+        _ => (
+            fmt.length_prefix.as_factor() / si::Prefix::Milli.as_factor(),
+            format!(" {}m", fmt.length_prefix.as_str()),
+        ),
+    };
+
+    Grid::new("surface_coefficients")
+        .striped(true)
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (j, coefficient) in (1..).zip(coefficients.iter_mut()) {
+                let label = ui.label(match kind {
+                    SurfaceKind::ZernikeSag => format!("Z{j}"),
+                    SurfaceKind::XyPolynomial => match xy_polynomial::exponents(j - 1) {
+                        Some((i, j)) => monomial(i, j),
+                        None => format!("#{j}"),
+                    },
+                    SurfaceKind::Binary2 => format!("ρ{}", superscript(2 * j as i32)),
+                    _ => format!("#{j}"),
+                });
+
+                // Terms past the limit of the ordering are kept, but do not take part in the sag
+                if max_terms.is_some_and(|max_terms| j > max_terms) {
+                    label.on_hover_text("Unused by the current ordering");
+                }
+
+                let mut value = *coefficient * factor;
+                ui.add(
+                    DragValue::new(&mut value)
+                        .suffix(&suffix)
+                        .speed(0.0)
+                        .custom_parser(|input| input.parse::<f64>().ok())
+                        .custom_formatter(|value, _| format!("{value:+.*e}", fmt.decimal_places)),
+                );
+                *coefficient = value / factor;
+
+                ui.end_row();
+            }
+        });
+}
+
+/// Name of the term x^`i` y^`j`, omitting the variables that are not present.
+fn monomial(i: u32, j: u32) -> String {
+    let variable = |name: &str, power: u32| match power {
+        0 => String::new(),
+        1 => name.to_owned(),
+        _ => format!("{name}{}", superscript(power as i32)),
+    };
+
+    variable("x", i) + &variable("y", j)
+}
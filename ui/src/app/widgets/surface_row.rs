@@ -1,9 +1,13 @@
-use egui::{ComboBox, DragValue, Layout, Response, Ui};
+use egui::{Button, ComboBox, DragValue, Layout, Response, Ui};
 use egui_extras::TableRow;
-use optics::surface::{Field, SurfaceKind};
+use optics::surface::{Field, Surface, SurfaceKind};
 
 use crate::app::{
-    State, formatting::Formatting, si, tabs::SurfaceEditor, widgets::material_index_optional,
+    State,
+    formatting::Formatting,
+    si,
+    tabs::SurfaceEditor,
+    widgets::{material_index_optional, wavelength},
 };
 
 pub struct SurfaceRow;
@@ -28,37 +32,69 @@ impl SurfaceRow {
         row.set_selected(row_index == editor.row);
         row.set_overline(row_index == 1 || row_index == state.system.surfaces.len() - 1);
 
+        let is_stop = row_index == state.system.stop();
         let surface = &mut state.system.surfaces[row_index];
         let kind = surface.kind();
-        let data = surface.data_mut();
 
         // Index
         row.col(|ui| {
             ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
-                ui.label(row_index.to_string());
+                if is_stop {
+                    ui.strong(format!("STO {row_index}"));
+                } else {
+                    ui.label(row_index.to_string());
+                }
             });
         });
 
         // Suface type
         row.col(|ui| {
-            ui.label(kind.name());
+            if kind == SurfaceKind::Object || kind == SurfaceKind::Image {
+                ui.label(kind.name());
+            } else if surface_kind(ui, surface).clicked() {
+                editor.row = row_index;
+            }
         });
 
+        let data = surface.data_mut();
+
         for (index, field) in kind.fields().iter().enumerate() {
             let mut field_data = &mut data[index];
 
             row.col(|ui| {
                 ui.with_layout(Layout::default().with_cross_justify(true), |ui| {
                     let response = match field {
-                        Some("curvature") => curvature(ui, &mut field_data, &state.formatting),
+                        Some("curvature" | "curvature_x") => {
+                            curvature(ui, &mut field_data, &state.formatting)
+                        }
                         Some(
                             "thickness" | "semi_diameter" | "translation_x" | "translation_y"
-                            | "translation_z",
+                            | "translation_z" | "focal_length" | "norm_radius",
                         ) => length(ui, &mut field_data, &state.formatting),
-                        Some("rotation_x" | "rotation_y" | "rotation_z") => {
+                        Some("rotation_x" | "rotation_y" | "rotation_z" | "grating_angle") => {
                             angle(ui, &mut field_data, &state.formatting)
                         }
                         Some("rotation_order") => rotation_order(ui, &mut field_data),
+                        Some("opd_mode") => opd_mode(ui, &mut field_data),
+                        Some("zernike_ordering") => zernike_ordering(ui, &mut field_data),
+                        Some("line_density") => {
+                            line_density(ui, &mut field_data, &state.formatting)
+                        }
+                        Some("diffraction_order") => diffraction_order(ui, &mut field_data),
+                        Some("design_wavelength") => {
+                            wavelength(ui, field_data.into(), &state.formatting)
+                        }
+                        Some("mirror") => flag(ui, &mut field_data),
+                        Some("conic" | "conic_x") => {
+                            unitless(ui, &mut field_data, &state.formatting)
+                        }
+                        Some(
+                            name @ ("asphere_r2" | "asphere_r4" | "asphere_r6" | "asphere_r8"
+                            | "asphere_r10" | "asphere_r12" | "asphere_r14" | "asphere_r16"),
+                        ) => {
+                            let power = name["asphere_r".len()..].parse().unwrap();
+                            coefficient(ui, &mut field_data, &state.formatting, power)
+                        }
                         Some("material_index") => material_index_optional(
                             ui,
                             field_data.into(),
@@ -88,6 +124,10 @@ impl SurfaceRow {
             if kind != SurfaceKind::Object {
                 if ui.button("Add surface before").clicked() {
                     state.system.surfaces.insert(row_index, Default::default());
+
+                    if row_index <= state.system.stop_index as usize {
+                        state.system.stop_index += 1;
+                    }
                 }
             }
 
@@ -97,20 +137,42 @@ impl SurfaceRow {
                         .system
                         .surfaces
                         .insert(row_index + 1, Default::default());
+
+                    if row_index < state.system.stop_index as usize {
+                        state.system.stop_index += 1;
+                    }
                 }
             }
 
             if kind != SurfaceKind::Object && kind != SurfaceKind::Image {
                 ui.separator();
 
+                if ui
+                    .add_enabled(!is_stop, Button::new("Set as stop"))
+                    .clicked()
+                {
+                    state.system.stop_index = row_index as u32;
+                }
+
+                ui.separator();
+
                 if ui.button("Duplicate surface").clicked() {
                     let new_surface = state.system.surfaces[row_index].clone();
                     state.system.surfaces.insert(row_index + 1, new_surface);
+
+                    if row_index < state.system.stop_index as usize {
+                        state.system.stop_index += 1;
+                    }
                 }
 
                 if ui.button("Delete surface").clicked() {
                     state.system.surfaces.remove(row_index);
                     editor.row = editor.row.min(state.system.surfaces.len() - 1);
+
+                    // The stop moves to the surface that takes the place of a deleted stop
+                    if row_index < state.system.stop_index as usize {
+                        state.system.stop_index -= 1;
+                    }
                 }
             }
         });
@@ -144,8 +206,10 @@ fn curvature(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
 }
 
 fn length(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
-    let length: &mut f32 = field.into();
+    length_value(ui, field.into(), fmt)
+}
 
+pub(crate) fn length_value(ui: &mut Ui, length: &mut f32, fmt: &Formatting) -> Response {
     let factor = fmt.length_prefix.as_factor() / si::Prefix::Milli.as_factor();
     let suffix = format!(" {}m", fmt.length_prefix.as_str());
 
@@ -170,8 +234,10 @@ fn length(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
 }
 
 fn angle(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
-    let angle: &mut f32 = field.into();
+    angle_value(ui, field.into(), fmt)
+}
 
+pub(crate) fn angle_value(ui: &mut Ui, angle: &mut f32, fmt: &Formatting) -> Response {
     ui.add(
         DragValue::new(angle)
             .suffix("°")
@@ -188,6 +254,31 @@ fn angle(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
     )
 }
 
+/// Grating lines per micrometer, shown in lines per millimeter.
+fn line_density(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
+    let line_density: &mut f32 = field.into();
+
+    let factor = si::Prefix::Milli.as_factor() / si::Prefix::Micro.as_factor();
+
+    let mut value = *line_density * factor;
+    let response = ui.add(
+        DragValue::new(&mut value)
+            .suffix(" lines/mm")
+            .speed(1.0)
+            .range(0.0..=f64::INFINITY)
+            .fixed_decimals(fmt.decimal_places),
+    );
+
+    *line_density = value / factor;
+    response
+}
+
+fn diffraction_order(ui: &mut Ui, field: &mut Field) -> Response {
+    let order: &mut i32 = field.into();
+
+    ui.add(DragValue::new(order).speed(0.05))
+}
+
 fn rotation_order(ui: &mut Ui, field: &mut Field) -> Response {
     const ROTATION_ORDER_OPTIONS: &[&str] = &["XYZ", "XZY", "YXZ", "YZX", "ZXY", "ZYX"];
     let order: &mut u32 = field.into();
@@ -202,3 +293,108 @@ fn rotation_order(ui: &mut Ui, field: &mut Field) -> Response {
     *order = picked as u32;
     response
 }
+
+fn flag(ui: &mut Ui, field: &mut Field) -> Response {
+    let flag: &mut u32 = field.into();
+    let mut checked = *flag != 0;
+
+    let response = ui.checkbox(&mut checked, "");
+
+    *flag = checked as u32;
+    response
+}
+
+fn opd_mode(ui: &mut Ui, field: &mut Field) -> Response {
+    const OPD_MODE_OPTIONS: &[&str] = &["Ideal", "Quadratic"];
+    let mode: &mut u32 = field.into();
+    let mut picked: usize = *mode as usize;
+
+    let response = ComboBox::from_id_salt("opd_mode")
+        .width(ui.available_width())
+        .show_index(ui, &mut picked, OPD_MODE_OPTIONS.len(), |i| {
+            OPD_MODE_OPTIONS[i]
+        });
+
+    *mode = picked as u32;
+    response
+}
+
+fn zernike_ordering(ui: &mut Ui, field: &mut Field) -> Response {
+    const ZERNIKE_ORDERING_OPTIONS: &[&str] = &["Fringe", "Standard"];
+    let ordering: &mut u32 = field.into();
+    let mut picked: usize = *ordering as usize;
+
+    let response = ComboBox::from_id_salt("zernike_ordering")
+        .width(ui.available_width())
+        .show_index(ui, &mut picked, ZERNIKE_ORDERING_OPTIONS.len(), |i| {
+            ZERNIKE_ORDERING_OPTIONS[i]
+        });
+
+    *ordering = picked as u32;
+    response
+}
+
+fn surface_kind(ui: &mut Ui, surface: &mut Surface) -> Response {
+    let mut picked = surface.kind();
+
+    let response = ComboBox::from_id_salt("surface_kind")
+        .width(ui.available_width())
+        .selected_text(picked.name())
+        .show_ui(ui, |ui| {
+            for &kind in SurfaceKind::ALL {
+                if kind != SurfaceKind::Object && kind != SurfaceKind::Image {
+                    ui.selectable_value(&mut picked, kind, kind.name());
+                }
+            }
+        })
+        .response;
+
+    surface.set_kind(picked);
+    response
+}
+
+fn unitless(ui: &mut Ui, field: &mut Field, fmt: &Formatting) -> Response {
+    let value: &mut f32 = field.into();
+
+    ui.add(
+        DragValue::new(value)
+            .speed(0.01)
+            .fixed_decimals(fmt.decimal_places)
+            .custom_formatter(|value, _| format!("{value:+4.*}", fmt.decimal_places)),
+    )
+}
+
+/// Coefficient of a polynomial term in r^`power`, which has units of length^(1 - `power`).
+fn coefficient(ui: &mut Ui, field: &mut Field, fmt: &Formatting, power: i32) -> Response {
+    let coefficient: &mut f32 = field.into();
+
+    let factor = (fmt.length_prefix.as_factor() / si::Prefix::Milli.as_factor()).powi(1 - power);
+    let suffix = format!(" {}m{}", fmt.length_prefix.as_str(), superscript(1 - power));
+
+    let mut value = *coefficient * factor;
+    let response = ui.add(
+        DragValue::new(&mut value)
+            .suffix(suffix)
+            .speed(0.0)
+            .custom_parser(|input| input.parse::<f64>().ok())
+            .custom_formatter(|value, _| format!("{value:+.*e}", fmt.decimal_places)),
+    );
+
+    *coefficient = value / factor;
+    response
+}
+
+pub(crate) fn superscript(value: i32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+    let magnitude = value.unsigned_abs().to_string();
+    let digits = magnitude
+        .chars()
+        .map(|digit| DIGITS[digit.to_digit(10).unwrap() as usize]);
+
+    if value < 0 {
+        core::iter::once('⁻').chain(digits).collect()
+    } else {
+        digits.collect()
+    }
+}
@@ -0,0 +1,20 @@
+use egui::{ColorImage, Context, TextureHandle, TextureId, TextureOptions};
+
+/// Replaces the image of `texture` with `image`, loading it the first time. Images are sampled
+/// without filtering, so that each value of an analysis is shown as a square.
+pub fn update_texture(
+    ctx: &Context,
+    texture: &mut Option<TextureHandle>,
+    name: &str,
+    image: ColorImage,
+) -> TextureId {
+    match texture {
+        Some(texture) => {
+            texture.set(image, TextureOptions::NEAREST);
+            texture.id()
+        }
+        None => texture
+            .insert(ctx.load_texture(name, image, TextureOptions::NEAREST))
+            .id(),
+    }
+}
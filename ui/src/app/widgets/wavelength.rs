@@ -1,4 +1,4 @@
-use egui::{DragValue, Response, Ui};
+use egui::{Color32, DragValue, Response, Ui};
 use optics::ray::Wavelength;
 
 use crate::app::{formatting::Formatting, si};
@@ -32,3 +32,19 @@ pub fn wavelength(ui: &mut Ui, value: &mut Wavelength, fmt: &Formatting) -> Resp
 
     response
 }
+
+/// Color used to plot data of `wavelength`, close to the hue of visible light. Wavelengths outside
+/// of the visible spectrum are drawn with the color of its nearest end.
+pub fn wavelength_color(wavelength: Wavelength) -> Color32 {
+    // Piecewise linear approximation of the spectrum, in micrometers
+    let (r, g, b) = match wavelength.clamp(0.38, 0.75) {
+        w if w < 0.44 => ((0.44 - w) / 0.06, 0.0, 1.0),
+        w if w < 0.49 => (0.0, (w - 0.44) / 0.05, 1.0),
+        w if w < 0.51 => (0.0, 1.0, (0.51 - w) / 0.02),
+        w if w < 0.58 => ((w - 0.51) / 0.07, 1.0, 0.0),
+        w if w < 0.645 => (1.0, (0.645 - w) / 0.065, 0.0),
+        _ => (1.0, 0.0, 0.0),
+    };
+
+    Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}